@@ -9,6 +9,9 @@ use fhtmx::prelude::Render;
 pub trait FhtmxActixRender {
     /// Renders as a `ContentType::html()`
     fn render_response(&self) -> HttpResponse;
+
+    /// Renders as a standalone XML document with an `image/svg+xml` content type
+    fn render_svg_response(&self) -> HttpResponse;
 }
 
 impl<T: Render> FhtmxActixRender for T {
@@ -18,6 +21,13 @@ impl<T: Render> FhtmxActixRender for T {
             .content_type(ContentType::html())
             .body(html_body)
     }
+
+    fn render_svg_response(&self) -> HttpResponse {
+        let svg_body = self.render_xml();
+        HttpResponse::Ok()
+            .content_type("image/svg+xml")
+            .body(svg_body)
+    }
 }
 
 /// Is the HX-Request header present
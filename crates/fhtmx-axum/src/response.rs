@@ -8,6 +8,9 @@ use http::{HeaderName, header, request};
 pub trait FhtmxAxumResponse {
     /// Build a Html response
     fn render_response(&self) -> Response;
+
+    /// Build a `image/svg+xml` response with the standalone XML document
+    fn render_svg_response(&self) -> Response;
 }
 
 impl<T: Render> FhtmxAxumResponse for T {
@@ -19,6 +22,11 @@ impl<T: Render> FhtmxAxumResponse for T {
         )
             .into_response()
     }
+
+    fn render_svg_response(&self) -> Response {
+        let svg_body = self.render_xml();
+        ([(header::CONTENT_TYPE, mime::IMAGE_SVG.as_ref())], svg_body).into_response()
+    }
 }
 
 /// Always “true” on htmx requests.
@@ -0,0 +1,347 @@
+use darling::{FromAttributes, FromMeta};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Expr, FnArg, GenericParam, Ident, ItemFn, Lifetime, Pat, ReturnType, Type,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+};
+
+/// Default value of a prop: `#[prop(default)]` or `#[prop(default = expr)]`
+enum PropDefault {
+    Trait,
+    Expr(Expr),
+}
+
+impl FromMeta for PropDefault {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self::Trait)
+    }
+
+    fn from_expr(expr: &Expr) -> darling::Result<Self> {
+        Ok(Self::Expr(expr.clone()))
+    }
+}
+
+#[derive(FromAttributes)]
+#[darling(attributes(prop))]
+struct PropAttrs {
+    #[darling(default)]
+    default: Option<PropDefault>,
+    #[darling(default)]
+    into: bool,
+}
+
+enum PropKind {
+    /// Taken by the constructor
+    Required,
+    /// Set with a setter, `None` by default
+    Optional(Type),
+    /// Set with a setter, starts with the default value
+    Defaulted(PropDefault),
+    /// Filled with `add` and `add_children`
+    Children,
+}
+
+struct Prop {
+    ident: Ident,
+    pat: Pat,
+    ty: Type,
+    into: bool,
+    kind: PropKind,
+}
+
+impl Prop {
+    fn from_arg(arg: &mut FnArg, elided: &mut ElidedLifetimes) -> darling::Result<Self> {
+        let FnArg::Typed(arg) = arg else {
+            return Err(darling::Error::custom("components can't take `self`").with_span(arg));
+        };
+        let Pat::Ident(pat_ident) = arg.pat.as_ref() else {
+            return Err(darling::Error::custom("component props must be named").with_span(&arg.pat));
+        };
+        let ident = pat_ident.ident.clone();
+        let attrs = PropAttrs::from_attributes(&arg.attrs)?;
+        arg.attrs.retain(|o| !o.path().is_ident("prop"));
+        elided.visit_type_mut(&mut arg.ty);
+        let kind = if ident == "children" {
+            if attrs.default.is_some() || attrs.into {
+                return Err(
+                    darling::Error::custom("the `children` prop doesn't take options")
+                        .with_span(&ident),
+                );
+            }
+            PropKind::Children
+        } else if let Some(default) = attrs.default {
+            PropKind::Defaulted(default)
+        } else if let Some(inner) = option_inner(&arg.ty) {
+            PropKind::Optional(inner.clone())
+        } else {
+            PropKind::Required
+        };
+        Ok(Self {
+            ident,
+            pat: arg.pat.as_ref().clone(),
+            ty: arg.ty.as_ref().clone(),
+            into: attrs.into,
+            kind,
+        })
+    }
+
+    /// Type taken by the constructor or the setter and the expression converting it
+    fn arg(&self, ty: &Type) -> (TokenStream, TokenStream) {
+        let ident = &self.ident;
+        if self.into {
+            (quote! { impl Into<#ty> }, quote! { #ident.into() })
+        } else {
+            (quote! { #ty }, quote! { #ident })
+        }
+    }
+}
+
+/// Names the elided lifetimes of the props (`&str`, `Cow<'_, str>`), as they are stored in the
+/// props struct
+struct ElidedLifetimes {
+    lifetime: Lifetime,
+    found: bool,
+}
+
+impl VisitMut for ElidedLifetimes {
+    fn visit_type_reference_mut(&mut self, ty: &mut syn::TypeReference) {
+        if ty.lifetime.is_none() {
+            ty.lifetime = Some(self.lifetime.clone());
+            self.found = true;
+        }
+        visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime.clone();
+            self.found = true;
+        }
+    }
+
+    // Function types have their own elision rules
+    fn visit_type_bare_fn_mut(&mut self, _: &mut syn::TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(
+        &mut self,
+        _: &mut syn::ParenthesizedGenericArguments,
+    ) {
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+    let segment = ty.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+fn returns_html_element(ty: &Type) -> bool {
+    matches!(ty, Type::Path(ty) if ty.path.segments.last().is_some_and(|o| o.ident == "HtmlElement"))
+}
+
+fn to_pascal_case(s: &str) -> String {
+    s.split('_')
+        .filter(|o| !o.is_empty())
+        .map(|o| {
+            let mut chars = o.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+pub(crate) fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new(
+            args.span(),
+            "`#[component]` doesn't take arguments",
+        ));
+    }
+    let mut func: ItemFn = syn::parse2(item)?;
+    if let Some(param) = func
+        .sig
+        .generics
+        .params
+        .iter()
+        .find(|o| !matches!(o, GenericParam::Lifetime(_)))
+    {
+        return Err(syn::Error::new(
+            param.span(),
+            "components can only be generic over lifetimes",
+        ));
+    }
+    let ReturnType::Type(_, ret) = func.sig.output.clone() else {
+        return Err(syn::Error::new(
+            func.sig.ident.span(),
+            "components must return an element or a node",
+        ));
+    };
+    let mut elided = ElidedLifetimes {
+        lifetime: Lifetime::new("'props", func.sig.ident.span()),
+        found: false,
+    };
+    let mut errors = darling::Error::accumulator();
+    let props = func
+        .sig
+        .inputs
+        .iter_mut()
+        .filter_map(|o| errors.handle(Prop::from_arg(o, &mut elided)))
+        .collect::<Vec<_>>();
+    errors.finish()?;
+    let mut lifetimes = func.sig.generics.params.iter().cloned().collect::<Vec<_>>();
+    let declared = lifetimes
+        .iter()
+        .any(|o| matches!(o, GenericParam::Lifetime(x) if x.lifetime == elided.lifetime));
+    if elided.found && !declared {
+        lifetimes.push(GenericParam::Lifetime(syn::LifetimeParam::new(
+            elided.lifetime,
+        )));
+    }
+    let (generics, ty_generics) = if lifetimes.is_empty() {
+        (quote! {}, quote! {})
+    } else {
+        let params = lifetimes.iter();
+        let args = lifetimes.iter().map(|o| match o {
+            GenericParam::Lifetime(x) => &x.lifetime,
+            _ => unreachable!(),
+        });
+        (quote! { <#(#params),*> }, quote! { <#(#args),*> })
+    };
+
+    let vis = &func.vis;
+    let fn_attrs = &func.attrs;
+    let name = &func.sig.ident;
+    let props_name = format_ident!("{}Props", to_pascal_case(&name.to_string()));
+    let props_doc = format!("Props of the [`{name}`] component.");
+
+    let fields = props
+        .iter()
+        .map(|Prop { ident, ty, .. }| quote! { #ident: #ty });
+    let required = props
+        .iter()
+        .filter(|o| matches!(o.kind, PropKind::Required))
+        .map(|o| {
+            let ident = &o.ident;
+            let (ty, _) = o.arg(&o.ty);
+            quote! { #ident: #ty }
+        });
+    let inits = props.iter().map(|o| {
+        let ident = &o.ident;
+        match &o.kind {
+            PropKind::Required => {
+                let (_, value) = o.arg(&o.ty);
+                quote! { #ident: #value }
+            }
+            PropKind::Optional(_) => quote! { #ident: None },
+            PropKind::Defaulted(PropDefault::Trait) => {
+                quote! { #ident: Default::default() }
+            }
+            PropKind::Defaulted(PropDefault::Expr(x)) => quote! { #ident: #x },
+            PropKind::Children => quote! { #ident: Vec::new() },
+        }
+    });
+    let setters = props.iter().map(|o| {
+        let ident = &o.ident;
+        let doc = format!("Sets the `{ident}` prop.");
+        match &o.kind {
+            PropKind::Required => quote! {},
+            PropKind::Optional(inner) => {
+                let (ty, value) = o.arg(inner);
+                quote! {
+                    #[doc = #doc]
+                    #vis fn #ident(mut self, #ident: #ty) -> Self {
+                        self.#ident = Some(#value);
+                        self
+                    }
+                }
+            }
+            PropKind::Defaulted(_) => {
+                let (ty, value) = o.arg(&o.ty);
+                quote! {
+                    #[doc = #doc]
+                    #vis fn #ident(mut self, #ident: #ty) -> Self {
+                        self.#ident = #value;
+                        self
+                    }
+                }
+            }
+            PropKind::Children => quote! {
+                /// Adds a child.
+                #vis fn add(mut self, node: impl ::fhtmx::prelude::IntoNode) -> Self {
+                    self.#ident.push(::fhtmx::prelude::IntoNode::into_node(node));
+                    self
+                }
+
+                /// Adds multiple children.
+                #vis fn add_children(
+                    mut self,
+                    nodes: impl IntoIterator<Item = impl ::fhtmx::prelude::IntoNode>,
+                ) -> Self {
+                    self.#ident
+                        .extend(nodes.into_iter().map(::fhtmx::prelude::IntoNode::into_node));
+                    self
+                }
+            },
+        }
+    });
+    let pats = props.iter().map(|o| &o.pat);
+    let stmts = &func.block.stmts;
+    let into_impl = if returns_html_element(&ret) {
+        quote! {
+            impl #generics ::fhtmx::prelude::IntoHtmlElement for #props_name #ty_generics {
+                fn into_element(self) -> ::fhtmx::prelude::HtmlElement {
+                    self.build()
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #generics ::fhtmx::prelude::IntoNode for #props_name #ty_generics {
+                fn into_node(self) -> ::fhtmx::prelude::HtmlNode {
+                    ::fhtmx::prelude::IntoNode::into_node(self.build())
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #[doc = #props_doc]
+        #vis struct #props_name #generics {
+            #(#fields),*
+        }
+
+        #(#fn_attrs)*
+        #vis fn #name #generics (#(#required),*) -> #props_name #ty_generics {
+            #props_name {
+                #(#inits),*
+            }
+        }
+
+        impl #generics #props_name #ty_generics {
+            #(#setters)*
+
+            /// Builds the component.
+            #vis fn build(self) -> #ret {
+                let Self { #(#pats),* } = self;
+                #(#stmts)*
+            }
+        }
+
+        #into_impl
+    })
+}
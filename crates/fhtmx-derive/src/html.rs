@@ -0,0 +1,598 @@
+use crate::tags::{HTML_TAGS, RESERVED_CUSTOM_ELEMENT_NAMES, SVG_TAGS, URL_ATTRS, VOID_TAGS};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    Expr, Ident, Lit, Pat, Token, braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{ParseStream, Parser},
+    token,
+};
+
+/// Template syntax accepted by the macro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Syntax {
+    /// `div class="x" { "text" (expr) }`, used by `html!`
+    Maud,
+    /// `<div class="x">"text" {expr}</div>`, used by `rsx!`
+    Jsx,
+}
+
+/// Namespace of the tags being parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Namespace {
+    Html,
+    Svg,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Ctx {
+    syntax: Syntax,
+    ns: Namespace,
+}
+
+enum Node {
+    Element(Element),
+    Text(Lit),
+    Expr(Expr),
+    If(If),
+    For(For),
+    Match(Match),
+}
+
+struct Element {
+    /// Call creating the element (`div()`, `svg_path()`, `dc_btn()`, `custom_element("x-a")`, ...)
+    ctor: TokenStream,
+    attrs: Vec<Attr>,
+    children: Vec<Node>,
+}
+
+struct Attr {
+    name: String,
+    span: Span,
+    value: AttrValue,
+}
+
+enum AttrValue {
+    /// `disabled`
+    Empty,
+    /// `disabled[expr]`
+    Toggle(Expr),
+    /// `id="x"` or `id=(expr)`
+    Value(Expr),
+}
+
+struct If {
+    cond: Cond,
+    then: Vec<Node>,
+    otherwise: Option<Else>,
+}
+
+enum Cond {
+    Expr(Expr),
+    Let(Box<Pat>, Expr),
+}
+
+enum Else {
+    If(Box<If>),
+    Nodes(Vec<Node>),
+}
+
+struct For {
+    pat: Box<Pat>,
+    expr: Expr,
+    body: Vec<Node>,
+}
+
+struct Match {
+    expr: Expr,
+    arms: Vec<Arm>,
+}
+
+struct Arm {
+    pat: Box<Pat>,
+    guard: Option<Expr>,
+    body: Vec<Node>,
+}
+
+/// Expands the template into builder calls
+pub(crate) fn expand(input: TokenStream, syntax: Syntax) -> TokenStream {
+    let ctx = Ctx {
+        syntax,
+        ns: Namespace::Html,
+    };
+    let nodes = match (|input: ParseStream| parse_nodes(input, ctx)).parse2(input) {
+        Ok(o) => o,
+        Err(e) => return e.to_compile_error(),
+    };
+    let body = match nodes.as_slice() {
+        [Node::Element(el)] => element_tokens(el),
+        _ if nodes.iter().all(Node::is_static) => {
+            let children = nodes.iter().map(Node::static_tokens);
+            quote! {
+                ::fhtmx::prelude::HtmlNode::Fragment(
+                    vec![#(::fhtmx::prelude::IntoNode::into_node(#children)),*]
+                )
+            }
+        }
+        _ => {
+            let acc = Ident::new("__nodes", Span::mixed_site());
+            let push = |x: TokenStream| {
+                quote! { #acc.push(::fhtmx::prelude::IntoNode::into_node(#x)); }
+            };
+            let stmts = nodes.iter().map(|o| o.stmt_tokens(&push));
+            quote! {{
+                let mut #acc = Vec::<::fhtmx::prelude::HtmlNode>::new();
+                #(#stmts)*
+                ::fhtmx::prelude::HtmlNode::Fragment(#acc)
+            }}
+        }
+    };
+    quote! {{
+        #[allow(unused_imports)]
+        use ::fhtmx::prelude::Element as _;
+        #body
+    }}
+}
+
+fn is_end(input: ParseStream, ctx: Ctx) -> bool {
+    input.is_empty()
+        || (ctx.syntax == Syntax::Jsx && input.peek(Token![<]) && input.peek2(Token![/]))
+}
+
+fn parse_nodes(input: ParseStream, ctx: Ctx) -> syn::Result<Vec<Node>> {
+    let mut res = Vec::new();
+    while !is_end(input, ctx) {
+        res.push(parse_node(input, ctx)?);
+    }
+    Ok(res)
+}
+
+fn parse_braced_nodes(input: ParseStream, ctx: Ctx) -> syn::Result<Vec<Node>> {
+    let content;
+    braced!(content in input);
+    let res = parse_nodes(&content, ctx)?;
+    if !content.is_empty() {
+        return Err(content.error("unexpected closing tag"));
+    }
+    Ok(res)
+}
+
+fn parse_node(input: ParseStream, ctx: Ctx) -> syn::Result<Node> {
+    if input.peek(Token![@]) {
+        input.parse::<Token![@]>()?;
+        let lookahead = input.lookahead1();
+        return if lookahead.peek(Token![if]) {
+            parse_if(input, ctx).map(Node::If)
+        } else if lookahead.peek(Token![for]) {
+            parse_for(input, ctx).map(Node::For)
+        } else if lookahead.peek(Token![match]) {
+            parse_match(input, ctx).map(Node::Match)
+        } else {
+            Err(lookahead.error())
+        };
+    }
+    if input.peek(Lit) {
+        return input.parse().map(Node::Text);
+    }
+    match ctx.syntax {
+        Syntax::Maud => {
+            if input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                return content.parse().map(Node::Expr);
+            }
+            if input.peek(Ident::peek_any) {
+                return parse_maud_element(input, ctx).map(Node::Element);
+            }
+            Err(input.error(
+                "expected an element, a string literal, `(expr)`, `@if`, `@for` or `@match`",
+            ))
+        }
+        Syntax::Jsx => {
+            if input.peek(token::Brace) {
+                let content;
+                braced!(content in input);
+                return content.parse().map(Node::Expr);
+            }
+            if input.peek(Token![<]) {
+                return parse_jsx_element(input, ctx).map(Node::Element);
+            }
+            Err(input.error(
+                "expected an element, a string literal, `{expr}`, `@if`, `@for` or `@match`",
+            ))
+        }
+    }
+}
+
+fn parse_if(input: ParseStream, ctx: Ctx) -> syn::Result<If> {
+    input.parse::<Token![if]>()?;
+    let cond = if input.peek(Token![let]) {
+        input.parse::<Token![let]>()?;
+        let pat = Pat::parse_multi_with_leading_vert(input)?;
+        input.parse::<Token![=]>()?;
+        Cond::Let(Box::new(pat), Expr::parse_without_eager_brace(input)?)
+    } else {
+        Cond::Expr(Expr::parse_without_eager_brace(input)?)
+    };
+    let then = parse_braced_nodes(input, ctx)?;
+    let otherwise = if input.peek(Token![@]) && input.peek2(Token![else]) {
+        input.parse::<Token![@]>()?;
+        input.parse::<Token![else]>()?;
+        if input.peek(Token![if]) {
+            Some(Else::If(Box::new(parse_if(input, ctx)?)))
+        } else {
+            Some(Else::Nodes(parse_braced_nodes(input, ctx)?))
+        }
+    } else {
+        None
+    };
+    Ok(If {
+        cond,
+        then,
+        otherwise,
+    })
+}
+
+fn parse_for(input: ParseStream, ctx: Ctx) -> syn::Result<For> {
+    input.parse::<Token![for]>()?;
+    let pat = Box::new(Pat::parse_multi_with_leading_vert(input)?);
+    input.parse::<Token![in]>()?;
+    let expr = Expr::parse_without_eager_brace(input)?;
+    let body = parse_braced_nodes(input, ctx)?;
+    Ok(For { pat, expr, body })
+}
+
+fn parse_match(input: ParseStream, ctx: Ctx) -> syn::Result<Match> {
+    input.parse::<Token![match]>()?;
+    let expr = Expr::parse_without_eager_brace(input)?;
+    let content;
+    braced!(content in input);
+    let mut arms = Vec::new();
+    while !content.is_empty() {
+        let pat = Box::new(Pat::parse_multi_with_leading_vert(&content)?);
+        let guard = if content.peek(Token![if]) {
+            content.parse::<Token![if]>()?;
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        content.parse::<Token![=>]>()?;
+        let body = parse_braced_nodes(&content, ctx)?;
+        if content.peek(Token![,]) {
+            content.parse::<Token![,]>()?;
+        }
+        arms.push(Arm { pat, guard, body });
+    }
+    Ok(Match { expr, arms })
+}
+
+fn parse_maud_element(input: ParseStream, ctx: Ctx) -> syn::Result<Element> {
+    let (tag, span) = parse_tag_name(input)?;
+    let (ctor, ns, is_void) = resolve_tag(&tag, span, ctx.ns)?;
+    let mut attrs = Vec::new();
+    while !input.peek(token::Brace) && !input.peek(Token![;]) {
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                format!("expected `{{` or `;` after the attributes of `{tag}`"),
+            ));
+        }
+        attrs.push(parse_attr(input, ctx)?);
+    }
+    let children = if input.peek(Token![;]) {
+        input.parse::<Token![;]>()?;
+        Vec::new()
+    } else {
+        parse_braced_nodes(input, Ctx { ns, ..ctx })?
+    };
+    if is_void && !children.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            format!("`{tag}` is a void element and can't have children"),
+        ));
+    }
+    Ok(Element {
+        ctor,
+        attrs,
+        children,
+    })
+}
+
+fn parse_jsx_element(input: ParseStream, ctx: Ctx) -> syn::Result<Element> {
+    input.parse::<Token![<]>()?;
+    let (tag, span) = parse_tag_name(input)?;
+    let (ctor, ns, is_void) = resolve_tag(&tag, span, ctx.ns)?;
+    let mut attrs = Vec::new();
+    while !input.peek(Token![>]) && !input.peek(Token![/]) {
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                format!("expected `>` or `/>` after the attributes of `{tag}`"),
+            ));
+        }
+        attrs.push(parse_attr(input, ctx)?);
+    }
+    if input.peek(Token![/]) {
+        input.parse::<Token![/]>()?;
+        input.parse::<Token![>]>()?;
+        return Ok(Element {
+            ctor,
+            attrs,
+            children: Vec::new(),
+        });
+    }
+    input.parse::<Token![>]>()?;
+    if is_void {
+        return Ok(Element {
+            ctor,
+            attrs,
+            children: Vec::new(),
+        });
+    }
+    let children = parse_nodes(input, Ctx { ns, ..ctx })?;
+    if input.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            format!("missing closing tag `</{tag}>`"),
+        ));
+    }
+    input.parse::<Token![<]>()?;
+    input.parse::<Token![/]>()?;
+    let (close, close_span) = parse_tag_name(input)?;
+    if close != tag {
+        return Err(syn::Error::new(
+            close_span,
+            format!("expected closing tag `</{tag}>`"),
+        ));
+    }
+    input.parse::<Token![>]>()?;
+    Ok(Element {
+        ctor,
+        attrs,
+        children,
+    })
+}
+
+/// Parses tag names like `div` or `my-widget`
+fn parse_tag_name(input: ParseStream) -> syn::Result<(String, Span)> {
+    let ident = Ident::parse_any(input)?;
+    let span = ident.span();
+    let mut name = ident.unraw().to_string();
+    while input.peek(Token![-]) && (input.peek2(Ident::peek_any) || input.peek2(syn::LitInt)) {
+        input.parse::<Token![-]>()?;
+        name.push('-');
+        if input.peek(syn::LitInt) {
+            name.push_str(&input.parse::<syn::LitInt>()?.to_string());
+        } else {
+            name.push_str(&Ident::parse_any(input)?.unraw().to_string());
+        }
+    }
+    Ok((name, span))
+}
+
+/// Gets the call creating the element, the namespace of its children and if it's a void element
+fn resolve_tag(
+    name: &str,
+    span: Span,
+    ns: Namespace,
+) -> syn::Result<(TokenStream, Namespace, bool)> {
+    let call = |ctor: &str| {
+        let ctor = Ident::new(ctor, span);
+        quote! { ::fhtmx::prelude::#ctor() }
+    };
+    if name == "svg" {
+        return Ok((call("svg"), Namespace::Svg, false));
+    }
+    match ns {
+        Namespace::Html if name.starts_with("dc_") => Ok((call(name), Namespace::Html, false)),
+        Namespace::Html if HTML_TAGS.contains(&name) => {
+            let ctor = if name == "main" { "main_tag" } else { name };
+            Ok((call(ctor), Namespace::Html, VOID_TAGS.contains(&name)))
+        }
+        Namespace::Html if is_custom_element_name(name) => Ok((
+            quote_spanned! { span=> ::fhtmx::prelude::custom_element(#name) },
+            Namespace::Html,
+            false,
+        )),
+        Namespace::Svg if SVG_TAGS.contains(&name) => {
+            let ns = if name == "foreignObject" {
+                Namespace::Html
+            } else {
+                Namespace::Svg
+            };
+            Ok((call(&format!("svg_{}", to_snake_case(name))), ns, false))
+        }
+        Namespace::Html => Err(syn::Error::new(span, format!("unknown html tag `{name}`"))),
+        Namespace::Svg => Err(syn::Error::new(span, format!("unknown svg tag `{name}`"))),
+    }
+}
+
+/// Same as `fhtmx::prelude::is_valid_custom_element_name` for the names the macro can parse
+fn is_custom_element_name(name: &str) -> bool {
+    name.starts_with(|o: char| o.is_ascii_lowercase())
+        && name.contains('-')
+        && !name.ends_with('-')
+        && !name.chars().any(|o| o.is_ascii_uppercase())
+        && !RESERVED_CUSTOM_ELEMENT_NAMES.contains(&name)
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 4);
+    for c in s.chars() {
+        if c.is_ascii_uppercase() {
+            res.push('_');
+            res.push(c.to_ascii_lowercase());
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+/// Parses names like `id`, `hx-get`, `xlink:href`, `@click` or `x-on:click.prevent`
+fn parse_attr_name(input: ParseStream) -> syn::Result<(String, Span)> {
+    let span = input.span();
+    let mut name = String::new();
+    loop {
+        if input.peek(Token![-]) && !name.is_empty() {
+            input.parse::<Token![-]>()?;
+            name.push('-');
+        } else if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            name.push(':');
+        } else if input.peek(Token![.]) && !name.is_empty() {
+            input.parse::<Token![.]>()?;
+            name.push('.');
+        } else if input.peek(Token![@]) && name.is_empty() {
+            input.parse::<Token![@]>()?;
+            name.push('@');
+        } else if name.is_empty() || name.ends_with(['-', ':', '.', '@']) {
+            if input.peek(Ident::peek_any) {
+                name.push_str(&Ident::parse_any(input)?.unraw().to_string());
+            } else if input.peek(syn::LitInt) && !name.is_empty() {
+                name.push_str(&input.parse::<syn::LitInt>()?.to_string());
+            } else {
+                return Err(input.error("expected an attribute name"));
+            }
+        } else {
+            break;
+        }
+    }
+    Ok((name, span))
+}
+
+fn parse_attr(input: ParseStream, ctx: Ctx) -> syn::Result<Attr> {
+    let (name, span) = parse_attr_name(input)?;
+    let value = if input.peek(token::Bracket) {
+        let content;
+        bracketed!(content in input);
+        AttrValue::Toggle(content.parse()?)
+    } else if input.peek(Token![=]) {
+        input.parse::<Token![=]>()?;
+        if input.peek(Lit) {
+            AttrValue::Value(Expr::Lit(syn::ExprLit {
+                attrs: Vec::new(),
+                lit: input.parse()?,
+            }))
+        } else {
+            let content;
+            match ctx.syntax {
+                Syntax::Maud if input.peek(token::Paren) => {
+                    parenthesized!(content in input);
+                }
+                Syntax::Jsx if input.peek(token::Brace) => {
+                    braced!(content in input);
+                }
+                Syntax::Maud => {
+                    return Err(input.error(format!(
+                        "expected a literal or `(expr)` as the value of `{name}`"
+                    )));
+                }
+                Syntax::Jsx => {
+                    return Err(input.error(format!(
+                        "expected a literal or `{{expr}}` as the value of `{name}`"
+                    )));
+                }
+            }
+            AttrValue::Value(content.parse()?)
+        }
+    } else {
+        AttrValue::Empty
+    };
+    if name == "class" && !matches!(value, AttrValue::Value(_)) {
+        return Err(syn::Error::new(span, "`class` expects a value"));
+    }
+    Ok(Attr { name, span, value })
+}
+
+impl Node {
+    fn is_static(&self) -> bool {
+        matches!(self, Node::Element(_) | Node::Text(_) | Node::Expr(_))
+    }
+
+    fn static_tokens(&self) -> TokenStream {
+        match self {
+            Node::Element(el) => element_tokens(el),
+            Node::Text(x) => quote! { #x },
+            Node::Expr(x) => quote! { #x },
+            Node::If(_) | Node::For(_) | Node::Match(_) => unreachable!(),
+        }
+    }
+
+    /// Statements adding the node, `push` receives the expression of each child
+    fn stmt_tokens(&self, push: &dyn Fn(TokenStream) -> TokenStream) -> TokenStream {
+        match self {
+            Node::If(x) => if_tokens(x, push),
+            Node::For(For { pat, expr, body }) => {
+                let body = body.iter().map(|o| o.stmt_tokens(push));
+                quote! { for #pat in #expr { #(#body)* } }
+            }
+            Node::Match(Match { expr, arms }) => {
+                let arms = arms.iter().map(|Arm { pat, guard, body }| {
+                    let guard = guard.as_ref().map(|o| quote! { if #o });
+                    let body = body.iter().map(|o| o.stmt_tokens(push));
+                    quote! { #pat #guard => { #(#body)* } }
+                });
+                quote! { match #expr { #(#arms)* } }
+            }
+            _ => push(self.static_tokens()),
+        }
+    }
+}
+
+fn if_tokens(x: &If, push: &dyn Fn(TokenStream) -> TokenStream) -> TokenStream {
+    let cond = match &x.cond {
+        Cond::Expr(e) => quote! { #e },
+        Cond::Let(pat, e) => quote! { let #pat = #e },
+    };
+    let then = x.then.iter().map(|o| o.stmt_tokens(push));
+    let otherwise = x.otherwise.as_ref().map(|o| match o {
+        Else::If(x) => {
+            let x = if_tokens(x, push);
+            quote! { else #x }
+        }
+        Else::Nodes(nodes) => {
+            let nodes = nodes.iter().map(|o| o.stmt_tokens(push));
+            quote! { else { #(#nodes)* } }
+        }
+    });
+    quote! { if #cond { #(#then)* } #otherwise }
+}
+
+fn attr_tokens(Attr { name, span, value }: &Attr) -> TokenStream {
+    let span = *span;
+    match value {
+        AttrValue::Empty => quote_spanned! { span=> .set_empty_attr(#name) },
+        AttrValue::Toggle(x) => {
+            quote_spanned! { span=> .set_opt_empty_attr((#x).then_some(#name)) }
+        }
+        AttrValue::Value(x) if name == "class" => quote_spanned! { span=> .add_class(#x) },
+        AttrValue::Value(x) if URL_ATTRS.contains(&name.as_str()) => {
+            quote_spanned! { span=> .set_url_attr(#name, #x) }
+        }
+        AttrValue::Value(x) if ["hx-", "sse-", "ws-"].iter().any(|o| name.starts_with(o)) => {
+            quote_spanned! { span=> .set_raw_attr(#name, #x) }
+        }
+        AttrValue::Value(x) => quote_spanned! { span=> .set_attr(#name, #x) },
+    }
+}
+
+fn element_tokens(el: &Element) -> TokenStream {
+    let ctor = &el.ctor;
+    let attrs = el.attrs.iter().map(attr_tokens);
+    let element = quote! { #ctor #(#attrs)* };
+    if el.children.iter().all(Node::is_static) {
+        let children = el.children.iter().map(Node::static_tokens);
+        quote! { #element #(.add(#children))* }
+    } else {
+        let acc = Ident::new("__el", Span::mixed_site());
+        let push = |x: TokenStream| quote! { #acc.add_mut(#x); };
+        let stmts = el.children.iter().map(|o| o.stmt_tokens(&push));
+        quote! {{
+            let mut #acc = #element;
+            #(#stmts)*
+            #acc
+        }}
+    }
+}
@@ -1,5 +1,9 @@
+mod component;
+mod html;
+mod tags;
 mod utils;
 
+use crate::html::Syntax;
 use crate::utils::{DaisyColorAttr, ExprOrString, Mode, PostProc};
 use darling::{FromDeriveInput, FromField, ast::Data};
 use proc_macro::TokenStream;
@@ -173,3 +177,109 @@ pub fn derive_html_view(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Builds html with a maud like syntax, expanding to the element builder calls
+/// (`div().class(..).add(..)`).
+///
+/// - Elements: `tag attrs* { children }` or `tag attrs*;` for elements without children. Inside
+///   `svg` the tags are svg elements (`path` becomes `svg_path()`), Daisy components can be used as
+///   tags (`dc_btn`) and names with a `-` are custom elements (`my-widget`).
+/// - Attributes: `name="literal"`, `name=(expr)`, `name` for empty attributes and `name[cond]`
+///   to set an empty attribute only when `cond` is true. Names can contain `-`, `:` and `.`
+///   (`hx-get`, `xlink:href`, `x-on:click.prevent`). Url attributes are set with `set_url_attr`,
+///   htmx attributes with `set_raw_attr` and `class` adds classes.
+/// - Children: string literals, `(expr)` for any `IntoNode` value, `@if`/`@if let` with
+///   `@else`, `@for pat in expr { .. }` and `@match expr { pat => { .. } }`.
+///
+/// A single root element returns that element, anything else returns an `HtmlNode::Fragment`.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let items = ["a", "b"];
+/// let logged_in = true;
+/// let page = html! {
+///     div class="card" hx-get="/refresh" {
+///         h1 { "Items" }
+///         ul {
+///             @for item in items {
+///                 li { (item) }
+///             }
+///         }
+///         @if logged_in {
+///             a href="/logout" { "Logout" }
+///         } @else {
+///             dc_btn class="btn-primary" { "Login" }
+///         }
+///         br;
+///     }
+/// };
+/// ```
+#[proc_macro]
+pub fn html(input: TokenStream) -> TokenStream {
+    html::expand(input.into(), Syntax::Maud).into()
+}
+
+/// Same as `html!` with a JSX like syntax: `<tag attrs*>children</tag>` or `<tag attrs*/>`,
+/// attribute values are literals or `{expr}` and the children are string literals, `{expr}` or
+/// the `@if`/`@for`/`@match` blocks of `html!`.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let name = "Karls";
+/// let card = rsx! {
+///     <div class="card">
+///         <p>"Hello " {name}</p>
+///         <input type="checkbox" checked[name.is_empty()]/>
+///     </div>
+/// };
+/// ```
+#[proc_macro]
+pub fn rsx(input: TokenStream) -> TokenStream {
+    html::expand(input.into(), Syntax::Jsx).into()
+}
+
+/// Turns a function into a component with named props, implementing `IntoNode` (and
+/// `IntoHtmlElement` when it returns an `HtmlElement`).
+///
+/// The function `my_card` becomes a constructor returning a `MyCardProps` builder:
+/// - Arguments without options are required and taken by the constructor.
+/// - `Option<T>` arguments are optional, they start as `None` and their setter takes a `T`.
+/// - `#[prop(default)]` and `#[prop(default = expr)]` arguments start with the default value.
+/// - `#[prop(into)]` makes the constructor or setter take an `impl Into<T>`.
+/// - A `children: Vec<HtmlNode>` argument is filled with `add` and `add_children`.
+/// - Props can borrow (`&str`, `Cow<'_, str>`): the elided lifetimes become the `'props` lifetime
+///   of the builder. Components can also declare lifetimes, but no type parameters.
+///
+/// The body runs on `build()`, or when the component is used as a node.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// #[component]
+/// fn panel(
+///     #[prop(into)] title: String,
+///     subtitle: Option<&str>,
+///     #[prop(default = "info".to_string(), into)] kind: String,
+///     #[prop(default)] open: bool,
+///     children: Vec<HtmlNode>,
+/// ) -> HtmlElement {
+///     details()
+///         .class(format!("panel panel-{kind}"))
+///         .set_opt_empty_attr(open.then_some("open"))
+///         .add(summary().add(title).add_opt(subtitle.map(|o| small().add(o))))
+///         .add_children(children)
+/// }
+///
+/// let res = div().add(panel("Settings").open(true).add(p().add("Content")));
+/// ```
+#[proc_macro_attribute]
+pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
+    component::expand(args.into(), item.into())
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,24 @@
+//! The tag lists of the `html!` macro can't be shared with `fhtmx` (which depends on this crate),
+//! so they are checked against its lists here
+
+#[path = "../src/tags.rs"]
+mod tags;
+
+use fhtmx::prelude::*;
+use std::collections::BTreeSet;
+
+fn set<'a>(x: &[&'a str]) -> BTreeSet<&'a str> {
+    x.iter().copied().collect()
+}
+
+#[test]
+fn tags_match_fhtmx() {
+    assert_eq!(set(tags::HTML_TAGS), set(HTML_ELEMENTS));
+    assert_eq!(set(tags::VOID_TAGS), set(VOID_ELEMENTS));
+    assert_eq!(set(tags::SVG_TAGS), set(SVG_ELEMENTS));
+    assert_eq!(
+        set(tags::RESERVED_CUSTOM_ELEMENT_NAMES),
+        set(RESERVED_CUSTOM_ELEMENT_NAMES)
+    );
+    assert_eq!(set(tags::URL_ATTRS), set(URL_ATTRS));
+}
@@ -0,0 +1,21 @@
+use fhtmx::prelude::*;
+
+#[component]
+fn generic<T: IntoNode>(content: T) -> HtmlElement {
+    div().add(content)
+}
+
+#[component]
+fn children_options(#[prop(default)] children: Vec<HtmlNode>) -> HtmlElement {
+    div().add_children(children)
+}
+
+#[component]
+fn unnamed((a, b): (&str, &str)) -> HtmlElement {
+    div().add(a).add(b)
+}
+
+#[component]
+fn no_return(title: &str) {}
+
+fn main() {}
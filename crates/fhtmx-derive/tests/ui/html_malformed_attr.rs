@@ -0,0 +1,9 @@
+use fhtmx::prelude::*;
+
+fn main() {
+    let title = "x";
+    let _ = html! { div title=title { "x" } };
+    let _ = html! { div hx- { "x" } };
+    let _ = html! { div class { "x" } };
+    let _ = rsx! { <div title=(title)></div> };
+}
@@ -0,0 +1,10 @@
+use fhtmx::prelude::*;
+
+fn main() {
+    let _ = html! {
+        div {
+            spam { "x" }
+        }
+    };
+    let _ = rsx! { <svg><rect/><circel/></svg> };
+}
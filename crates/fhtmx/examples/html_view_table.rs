@@ -36,19 +36,23 @@ impl UserInfo {
         }
     }
 
-    fn postproc(&self, card: HtmlElement) -> HtmlElement {
-        card.update_html_element(0, |body| {
-            body.insert_child(
-                1,
-                p().class("opacity-70 font-semibold text-sm")
-                    .add("Some description can go here..."),
-            )
-            .add(
-                dc_card_actions()
-                    .add(dc_btn().add("Edit"))
-                    .add(dc_btn().add_class("btn-error btn-soft").add("Remove")),
-            )
-        })
+    fn postproc(&self, mut card: HtmlElement) -> HtmlElement {
+        card.select_mut(".card-body > .card-title", |title| {
+            let description = p()
+                .class("opacity-70 font-semibold text-sm")
+                .add("Some description can go here...");
+            *title = fragment([title.clone(), description.into_node()]);
+        });
+        card.select_mut(".card-body", |body| {
+            if let Some(body) = body.as_element_mut() {
+                body.add_mut(
+                    dc_card_actions()
+                        .add(dc_btn().add("Edit"))
+                        .add(dc_btn().add_class("btn-error btn-soft").add("Remove")),
+                );
+            }
+        });
+        card
     }
 
     fn salary(&self) -> String {
@@ -0,0 +1,449 @@
+use crate::{
+    attribute::AttributeValue,
+    element::Element,
+    html_page::HtmlPage,
+    node::{HtmlNode, IntoNode},
+    validate::element_nodes,
+    walk::{Walk, walk_nodes},
+};
+use indexmap::IndexMap;
+use std::{borrow::Cow, collections::HashSet, fmt, iter::Enumerate, slice, vec};
+
+type Attrs = IndexMap<Cow<'static, str>, AttributeValue>;
+
+/// Form controls that need a label
+const FORM_CONTROLS: &[&str] = &["input", "select", "textarea"];
+
+/// `input` types that don't need a label: hidden, or buttons named by their value (image buttons
+/// are named by their `alt`)
+const UNLABELLED_INPUT_TYPES: &[&str] = &["button", "hidden", "reset", "submit"];
+
+/// Accessibility rules checked by `CheckA11y`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum A11yRule {
+    /// `img` without `alt` (decorative images should use `alt=""`)
+    ImageAlt,
+    /// `input type="image"` without `alt`, `aria-label`, `aria-labelledby` or `title`
+    InputImageAlt,
+    /// Form control without a `label`, `aria-label` or `aria-labelledby`
+    Label,
+    /// Button without an accessible name (eg: icon-only buttons)
+    ButtonName,
+    /// Link without an accessible name (eg: icon-only links)
+    LinkName,
+    /// Heading skipping levels (eg: a `h4` after a `h2`)
+    HeadingOrder,
+    /// `html` without a `lang`
+    HtmlHasLang,
+}
+
+impl A11yRule {
+    /// Name of the rule, the same used by axe-core
+    pub fn name(self) -> &'static str {
+        match self {
+            A11yRule::ImageAlt => "image-alt",
+            A11yRule::InputImageAlt => "input-image-alt",
+            A11yRule::Label => "label",
+            A11yRule::ButtonName => "button-name",
+            A11yRule::LinkName => "link-name",
+            A11yRule::HeadingOrder => "heading-order",
+            A11yRule::HtmlHasLang => "html-has-lang",
+        }
+    }
+}
+
+impl fmt::Display for A11yRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Accessibility issue found by `CheckA11y::check_a11y`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct A11yIssue {
+    pub rule: A11yRule,
+    pub message: String,
+    /// Path to the offending element, eg: `body > form[1] > input[0]` (see `ValidationError`)
+    pub path: String,
+}
+
+impl fmt::Display for A11yIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} at `{}`", self.rule, self.message, self.path)
+    }
+}
+
+/// Issues found by `CheckA11y::check_a11y`, in document order. It displays one issue per line
+/// (nothing when there are no issues), to use it in snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct A11yReport {
+    pub issues: Vec<A11yIssue>,
+}
+
+impl A11yReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    /// Issues breaking `rule`
+    pub fn by_rule(&self, rule: A11yRule) -> impl Iterator<Item = &A11yIssue> {
+        self.issues.iter().filter(move |o| o.rule == rule)
+    }
+}
+
+impl fmt::Display for A11yReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lints html for common accessibility issues:
+///
+/// - `img` without `alt`, and image buttons (`input type="image"`) without a name.
+/// - Form controls without an associated `label` (wrapping them or with a matching `for`),
+///   `aria-label` or `aria-labelledby`.
+/// - Buttons and links without an accessible name: text, `aria-label`, `aria-labelledby`,
+///   `title` or an `img` with `alt`.
+/// - Headings skipping levels.
+/// - `html` without `lang`.
+///
+/// Elements hidden with `aria-hidden="true"` or `hidden` are not checked.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let page = div()
+///     .add(img().src("/logo.png"))
+///     .add(button().add(icons::delete()))
+///     .add(button().aria_label("Delete").add(icons::delete()));
+/// assert_eq!(
+///     page.check_a11y().to_string(),
+///     "image-alt: `img` without `alt` at `div > img[0]`\n\
+///      button-name: `button` without an accessible name at `div > button[1]`"
+/// );
+/// ```
+pub trait CheckA11y {
+    fn check_a11y(&self) -> A11yReport;
+}
+
+impl<T: Element> CheckA11y for T {
+    fn check_a11y(&self) -> A11yReport {
+        let mut linter = Linter::default();
+        linter.collect_label_targets(self);
+        walk_nodes(self.children(), |o| linter.collect_node_label_targets(o));
+        linter.visit(self, None);
+        linter.visit_descendants();
+        linter.report
+    }
+}
+
+impl CheckA11y for HtmlNode {
+    fn check_a11y(&self) -> A11yReport {
+        let mut linter = Linter::default();
+        walk_nodes(slice::from_ref(self), |o| {
+            linter.collect_node_label_targets(o)
+        });
+        let roots = element_nodes(slice::from_ref(self));
+        for (i, node) in roots.iter().enumerate() {
+            linter.visit_node(node, (roots.len() > 1).then_some(i));
+            linter.visit_descendants();
+        }
+        linter.report
+    }
+}
+
+impl CheckA11y for HtmlPage {
+    fn check_a11y(&self) -> A11yReport {
+        self.clone().into_node().check_a11y()
+    }
+}
+
+fn attr_str<'a>(attrs: &'a Attrs, name: &str) -> Option<&'a str> {
+    attrs.get(name).map(|o| match o {
+        AttributeValue::Empty => "",
+        AttributeValue::Value(x) | AttributeValue::Raw(x) => x.as_str(),
+    })
+}
+
+fn has_name_attr(attrs: &Attrs) -> bool {
+    ["aria-label", "aria-labelledby", "title"]
+        .iter()
+        .any(|o| attr_str(attrs, o).is_some_and(|o| !o.trim().is_empty()))
+}
+
+fn is_hidden(attrs: &Attrs) -> bool {
+    attrs.contains_key("hidden") || attr_str(attrs, "aria-hidden") == Some("true")
+}
+
+/// Checks if the element is named by its attributes or content: text, `img` alts and named
+/// descendants. Raw and pre-rendered content is assumed to have a name.
+fn has_accessible_name<E: Element>(el: &E) -> bool {
+    if has_name_attr(el.attrs()) {
+        return true;
+    }
+    let mut named = false;
+    walk_nodes(el.children(), |o| {
+        let (tag, attrs) = match o {
+            HtmlNode::Text(x) | HtmlNode::Raw(x) => {
+                named = !x.trim().is_empty();
+                return if named { Walk::Stop } else { Walk::Continue };
+            }
+            HtmlNode::Prerendered(_) => {
+                named = true;
+                return Walk::Stop;
+            }
+            HtmlNode::Element(x) => (x.tag(), x.attrs()),
+            HtmlNode::SvgElement(x) => (x.tag(), x.attrs()),
+            _ => return Walk::Continue,
+        };
+        if is_hidden(attrs) || FORM_CONTROLS.contains(&tag) {
+            return Walk::SkipChildren;
+        }
+        named = has_name_attr(attrs)
+            || (tag == "img" && attr_str(attrs, "alt").is_some_and(|o| !o.trim().is_empty()));
+        if named { Walk::Stop } else { Walk::Continue }
+    });
+    named
+}
+
+/// An element being visited
+struct Frame<'a> {
+    /// Path segment, eg: `li[2]`
+    segment: String,
+    /// Whether the nearest `label` ancestor has a name
+    label_named: Option<bool>,
+    children: Enumerate<vec::IntoIter<&'a HtmlNode>>,
+}
+
+#[derive(Default)]
+struct Linter<'a> {
+    frames: Vec<Frame<'a>>,
+    /// Ids of the elements referenced by named labels (`label for="id"`)
+    label_targets: HashSet<&'a str>,
+    last_heading: Option<u8>,
+    report: A11yReport,
+}
+
+impl<'a> Linter<'a> {
+    fn collect_label_targets<E: Element>(&mut self, el: &'a E) {
+        if el.tag() == "label"
+            && let Some(id) = attr_str(el.attrs(), "for")
+            && has_accessible_name(el)
+        {
+            self.label_targets.insert(id);
+        }
+    }
+
+    fn collect_node_label_targets(&mut self, node: &'a HtmlNode) -> Walk {
+        if let HtmlNode::Element(x) = node {
+            self.collect_label_targets(x);
+        }
+        Walk::Continue
+    }
+
+    /// Visits the children of the pending elements, until all of them are done
+    fn visit_descendants(&mut self) {
+        while let Some(frame) = self.frames.last_mut() {
+            match frame.children.next() {
+                Some((i, node)) => self.visit_node(node, Some(i)),
+                None => {
+                    self.frames.pop();
+                }
+            }
+        }
+    }
+
+    fn visit_node(&mut self, node: &'a HtmlNode, index: Option<usize>) {
+        match node {
+            HtmlNode::Element(x) => self.visit(x, index),
+            HtmlNode::SvgElement(x) => self.visit(x, index),
+            _ => {}
+        }
+    }
+
+    /// Checks an element and schedules its children
+    fn visit<E: Element>(&mut self, el: &'a E, index: Option<usize>) {
+        let tag = el.tag();
+        let attrs = el.attrs();
+        if is_hidden(attrs) {
+            return;
+        }
+        let segment = match index {
+            Some(i) => format!("{tag}[{i}]"),
+            None => tag.to_string(),
+        };
+        let label_named = self.frames.last().and_then(|o| o.label_named);
+        let role = attr_str(attrs, "role");
+
+        match tag {
+            "html" if attr_str(attrs, "lang").is_none_or(|o| o.trim().is_empty()) => {
+                self.issue(A11yRule::HtmlHasLang, "`html` without `lang`", &segment);
+            }
+            "img"
+                if !attrs.contains_key("alt")
+                    && !has_name_attr(attrs)
+                    && !matches!(role, Some("presentation" | "none")) =>
+            {
+                self.issue(A11yRule::ImageAlt, "`img` without `alt`", &segment);
+            }
+            "input" if attr_str(attrs, "type").is_some_and(|o| o.eq_ignore_ascii_case("image")) => {
+                let named = attr_str(attrs, "alt").is_some_and(|o| !o.trim().is_empty())
+                    || has_name_attr(attrs);
+                if !named {
+                    let message = "`input type=\"image\"` without `alt`";
+                    self.issue(A11yRule::InputImageAlt, message, &segment);
+                }
+            }
+            "input" | "select" | "textarea" => {
+                let typ = attr_str(attrs, "type").unwrap_or_default();
+                let labelled = (tag == "input" && UNLABELLED_INPUT_TYPES.contains(&typ))
+                    || has_name_attr(attrs)
+                    || label_named == Some(true)
+                    || attr_str(attrs, "id").is_some_and(|o| self.label_targets.contains(o));
+                if !labelled {
+                    let message = format!("`{tag}` without a label");
+                    self.issue(A11yRule::Label, message, &segment);
+                }
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = tag.as_bytes()[1] - b'0';
+                if let Some(last) = self.last_heading
+                    && level > last + 1
+                {
+                    let message = format!("`{tag}` after `h{last}`");
+                    self.issue(A11yRule::HeadingOrder, message, &segment);
+                }
+                self.last_heading = Some(level);
+            }
+            _ => {}
+        }
+
+        if (tag == "button" || role == Some("button")) && !has_accessible_name(el) {
+            let message = format!("`{tag}` without an accessible name");
+            self.issue(A11yRule::ButtonName, message, &segment);
+        } else if tag == "a"
+            && role.is_none()
+            && attrs.contains_key("href")
+            && !has_accessible_name(el)
+        {
+            self.issue(
+                A11yRule::LinkName,
+                "`a` without an accessible name",
+                &segment,
+            );
+        }
+
+        let label_named = if tag == "label" {
+            Some(has_accessible_name(el))
+        } else {
+            label_named
+        };
+        self.frames.push(Frame {
+            segment,
+            label_named,
+            children: element_nodes(el.children()).into_iter().enumerate(),
+        });
+    }
+
+    fn issue(&mut self, rule: A11yRule, message: impl Into<String>, segment: &str) {
+        let path = self
+            .frames
+            .iter()
+            .map(|o| o.segment.as_str())
+            .chain([segment])
+            .collect::<Vec<_>>()
+            .join(" > ");
+        self.report.issues.push(A11yIssue {
+            rule,
+            message: message.into(),
+            path,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{components::*, html_element::*, node::fragment};
+
+    #[test]
+    fn reports_issues() {
+        let page = HtmlPage::new()
+            .add_body_node(h1().add("Title"))
+            .add_body_node(
+                section()
+                    .add(h3().add("Skipped a level"))
+                    .add(img().src("/a.png"))
+                    .add(a().href("/edit").add(icons::edit()))
+                    .add(div().role("button").add(icons::delete())),
+            )
+            .add_body_node(
+                form()
+                    .add(label().add(input().name("unnamed")))
+                    .add(label().set_attr("for", "other").add("Other"))
+                    .add(input().id("email").typ("email"))
+                    .add(select().add(option().add("Some option")))
+                    .add(button().add(icons::save()))
+                    .add(input().typ("image").src("/send.png"))
+                    .add(input().typ("image").src("/send.png").alt(" ")),
+            );
+        insta::assert_snapshot!(page.check_a11y(), @r#"
+        html-has-lang: `html` without `lang` at `html`
+        heading-order: `h3` after `h1` at `html > body[1] > section[1] > h3[0]`
+        image-alt: `img` without `alt` at `html > body[1] > section[1] > img[1]`
+        link-name: `a` without an accessible name at `html > body[1] > section[1] > a[2]`
+        button-name: `div` without an accessible name at `html > body[1] > section[1] > div[3]`
+        label: `input` without a label at `html > body[1] > form[2] > label[0] > input[0]`
+        label: `input` without a label at `html > body[1] > form[2] > input[2]`
+        label: `select` without a label at `html > body[1] > form[2] > select[3]`
+        button-name: `button` without an accessible name at `html > body[1] > form[2] > button[4]`
+        input-image-alt: `input type="image"` without `alt` at `html > body[1] > form[2] > input[5]`
+        input-image-alt: `input type="image"` without `alt` at `html > body[1] > form[2] > input[6]`
+        "#);
+    }
+
+    #[test]
+    fn accessible_page_passes() {
+        let page = HtmlPage::new()
+            .lang("en")
+            .add_body_node(theme_toogle())
+            .add_body_node(h1().add("Title"))
+            .add_body_node(
+                section()
+                    .add(h2().add("Section"))
+                    .add(img().src("/decoration.png").alt(""))
+                    .add(a().href("/").add(img().src("/logo.png").alt("Home")))
+                    .add(button().aria_label("Delete").add(icons::delete()))
+                    .add(button().add(icons::save()).add(span().add("Save")))
+                    .add(div().hidden().add(button().add(icons::edit()))),
+            )
+            .add_body_node(h3().add("Subsection"))
+            .add_body_node(h2().add("Another section"))
+            .add_body_node(
+                form()
+                    .add(label().add("Name ").add(input().name("name")))
+                    .add(label().set_attr("for", "email").add("Email"))
+                    .add(input().id("email").typ("email"))
+                    .add(textarea().aria_label("Comments"))
+                    .add(input().typ("hidden").name("token"))
+                    .add(input().typ("submit").value("Send"))
+                    .add(input().typ("image").src("/send.png").alt("Send"))
+                    .add(input().typ("IMAGE").src("/send.png").aria_label("Send")),
+            );
+        assert!(page.clone().render().contains("<html lang=\"en\">"));
+        assert_eq!(page.check_a11y().to_string(), "");
+        // Partials don't need a `html` element
+        assert!(fragment([h2(), h3()]).check_a11y().is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+use crate::{
+    attribute::{AttributeValue, IntoAttributeValue},
+    element::Element,
+    html_element::HtmlElement,
+    svg::SvgElement,
+};
+use pastey::paste;
+
+macro_rules! aria_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            /// `(variant, value)` pairs
+            pub(crate) const VALUES: &[(&str, &str)] = &[$((stringify!($variant), $value)),+];
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let s = match self {
+                    $($name::$variant => $value),+
+                };
+                f.write_str(s)
+            }
+        }
+
+        impl IntoAttributeValue for $name {
+            fn into_attr(self) -> Option<AttributeValue> {
+                Some(AttributeValue::Value(self.to_string()))
+            }
+        }
+    };
+}
+
+aria_enum!(
+    /// WAI-ARIA roles, used with `role()`
+    Role {
+        Alert = "alert",
+        AlertDialog = "alertdialog",
+        Application = "application",
+        Article = "article",
+        Banner = "banner",
+        Blockquote = "blockquote",
+        Button = "button",
+        Caption = "caption",
+        Cell = "cell",
+        Checkbox = "checkbox",
+        Code = "code",
+        ColumnHeader = "columnheader",
+        Combobox = "combobox",
+        Complementary = "complementary",
+        ContentInfo = "contentinfo",
+        Definition = "definition",
+        Deletion = "deletion",
+        Dialog = "dialog",
+        Document = "document",
+        Emphasis = "emphasis",
+        Feed = "feed",
+        Figure = "figure",
+        Form = "form",
+        Generic = "generic",
+        Grid = "grid",
+        GridCell = "gridcell",
+        Group = "group",
+        Heading = "heading",
+        Img = "img",
+        Insertion = "insertion",
+        Link = "link",
+        List = "list",
+        Listbox = "listbox",
+        ListItem = "listitem",
+        Log = "log",
+        Main = "main",
+        Marquee = "marquee",
+        Math = "math",
+        Menu = "menu",
+        Menubar = "menubar",
+        MenuItem = "menuitem",
+        MenuItemCheckbox = "menuitemcheckbox",
+        MenuItemRadio = "menuitemradio",
+        Meter = "meter",
+        Navigation = "navigation",
+        None = "none",
+        Note = "note",
+        Option = "option",
+        Paragraph = "paragraph",
+        Presentation = "presentation",
+        ProgressBar = "progressbar",
+        Radio = "radio",
+        RadioGroup = "radiogroup",
+        Region = "region",
+        Row = "row",
+        RowGroup = "rowgroup",
+        RowHeader = "rowheader",
+        Scrollbar = "scrollbar",
+        Search = "search",
+        Searchbox = "searchbox",
+        Separator = "separator",
+        Slider = "slider",
+        SpinButton = "spinbutton",
+        Status = "status",
+        Strong = "strong",
+        Subscript = "subscript",
+        Superscript = "superscript",
+        Switch = "switch",
+        Tab = "tab",
+        Table = "table",
+        Tablist = "tablist",
+        TabPanel = "tabpanel",
+        Term = "term",
+        Textbox = "textbox",
+        Time = "time",
+        Timer = "timer",
+        Toolbar = "toolbar",
+        Tooltip = "tooltip",
+        Tree = "tree",
+        TreeGrid = "treegrid",
+        TreeItem = "treeitem",
+    }
+);
+
+aria_enum!(
+    /// Values of `aria-checked` and `aria-pressed`, can be created from a `bool`
+    AriaTristate {
+        True = "true",
+        False = "false",
+        Mixed = "mixed",
+    }
+);
+
+impl From<bool> for AriaTristate {
+    fn from(value: bool) -> Self {
+        if value {
+            AriaTristate::True
+        } else {
+            AriaTristate::False
+        }
+    }
+}
+
+aria_enum!(
+    /// Values of `aria-current`
+    AriaCurrent {
+        Page = "page",
+        Step = "step",
+        Location = "location",
+        Date = "date",
+        Time = "time",
+        True = "true",
+        False = "false",
+    }
+);
+
+aria_enum!(
+    /// Values of `aria-haspopup`
+    AriaHasPopup {
+        True = "true",
+        False = "false",
+        Menu = "menu",
+        Listbox = "listbox",
+        Tree = "tree",
+        Grid = "grid",
+        Dialog = "dialog",
+    }
+);
+
+aria_enum!(
+    /// Values of `aria-invalid`
+    AriaInvalid {
+        True = "true",
+        False = "false",
+        Grammar = "grammar",
+        Spelling = "spelling",
+    }
+);
+
+aria_enum!(
+    /// Values of `aria-live`
+    AriaLive {
+        Off = "off",
+        Polite = "polite",
+        Assertive = "assertive",
+    }
+);
+
+aria_enum!(
+    /// Values of `aria-autocomplete`
+    AriaAutocomplete {
+        None = "none",
+        Inline = "inline",
+        List = "list",
+        Both = "both",
+    }
+);
+
+aria_enum!(
+    /// Values of `aria-orientation`
+    AriaOrientation {
+        Horizontal = "horizontal",
+        Vertical = "vertical",
+    }
+);
+
+aria_enum!(
+    /// Values of `aria-sort`
+    AriaSort {
+        None = "none",
+        Ascending = "ascending",
+        Descending = "descending",
+        Other = "other",
+    }
+);
+
+/// `(method, attribute, enum, (variant, value) pairs)` of a setter taking an enum
+pub(crate) type EnumSetter = (
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static [(&'static str, &'static str)],
+);
+
+macro_rules! set_aria_attr {
+    (bool: $($attr:ident = $name:literal),+ $(,)?) => {
+        $(
+            paste! {
+                #[doc = "Sets the `" $name "` attribute to `true` or `false`."]
+                pub fn $attr(self, value: bool) -> Self {
+                    self.set_attr($name, if value { "true" } else { "false" })
+                }
+            }
+        )+
+
+        /// `(method, attribute)` pairs of the setters taking a `bool`
+        pub(crate) const ARIA_BOOL_SETTERS: &[(&str, &str)] = &[$((stringify!($attr), $name)),+];
+    };
+
+    (id_refs: $($attr:ident = $name:literal),+ $(,)?) => {
+        $(
+            paste! {
+                #[doc = "Sets the `" $name "` attribute, taking an id or a list of ids.\nExample: `div().aria_describedby([\"hint\", \"error\"])`"]
+                pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
+                    self.set_attr($name, value)
+                }
+            }
+        )+
+
+        /// `(method, attribute)` pairs of the setters taking ids
+        pub(crate) const ARIA_ID_REFS_SETTERS: &[(&str, &str)] =
+            &[$((stringify!($attr), $name)),+];
+    };
+
+    (values: $($attr:ident = $name:literal),+ $(,)?) => {
+        $(
+            paste! {
+                #[doc = "Sets the `" $name "` attribute."]
+                pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
+                    self.set_attr($name, value)
+                }
+            }
+        )+
+
+        /// `(method, attribute)` pairs of the setters taking any value
+        pub(crate) const ARIA_VALUE_SETTERS: &[(&str, &str)] = &[$((stringify!($attr), $name)),+];
+    };
+
+    (enums: $($(#[$meta:meta])* $attr:ident = $name:literal: $t:ident),+ $(,)?) => {
+        $(
+            paste! {
+                #[doc = "Sets the `" $name "` attribute."]
+                $(#[$meta])*
+                pub fn $attr(self, value: impl Into<$t>) -> Self {
+                    self.set_attr($name, value.into())
+                }
+            }
+        )+
+
+        /// Setters taking an enum
+        pub(crate) const ARIA_ENUM_SETTERS: &[EnumSetter] =
+            &[$((stringify!($attr), $name, stringify!($t), $t::VALUES)),+];
+    };
+}
+
+macro_rules! implement_aria {
+    ($($t:ty),+) => {
+        $(
+            impl $t {
+                set_aria_attr!(
+                    bool:
+                    aria_atomic = "aria-atomic",
+                    aria_busy = "aria-busy",
+                    aria_disabled = "aria-disabled",
+                    aria_expanded = "aria-expanded",
+                    aria_hidden = "aria-hidden",
+                    aria_modal = "aria-modal",
+                    aria_multiline = "aria-multiline",
+                    aria_multiselectable = "aria-multiselectable",
+                    aria_readonly = "aria-readonly",
+                    aria_required = "aria-required",
+                    aria_selected = "aria-selected",
+                );
+
+                set_aria_attr!(
+                    id_refs:
+                    aria_activedescendant = "aria-activedescendant",
+                    aria_controls = "aria-controls",
+                    aria_describedby = "aria-describedby",
+                    aria_details = "aria-details",
+                    aria_errormessage = "aria-errormessage",
+                    aria_flowto = "aria-flowto",
+                    aria_labelledby = "aria-labelledby",
+                    aria_owns = "aria-owns",
+                );
+
+                set_aria_attr!(
+                    values:
+                    aria_colcount = "aria-colcount",
+                    aria_colindex = "aria-colindex",
+                    aria_colspan = "aria-colspan",
+                    aria_description = "aria-description",
+                    aria_keyshortcuts = "aria-keyshortcuts",
+                    aria_label = "aria-label",
+                    aria_level = "aria-level",
+                    aria_placeholder = "aria-placeholder",
+                    aria_posinset = "aria-posinset",
+                    aria_relevant = "aria-relevant",
+                    aria_roledescription = "aria-roledescription",
+                    aria_rowcount = "aria-rowcount",
+                    aria_rowindex = "aria-rowindex",
+                    aria_rowspan = "aria-rowspan",
+                    aria_setsize = "aria-setsize",
+                    aria_valuemax = "aria-valuemax",
+                    aria_valuemin = "aria-valuemin",
+                    aria_valuenow = "aria-valuenow",
+                    aria_valuetext = "aria-valuetext",
+                );
+
+                set_aria_attr!(
+                    enums:
+                    /// Example: `div().role(Role::Checkbox).aria_checked(AriaTristate::Mixed)`
+                    aria_checked = "aria-checked": AriaTristate,
+                    /// Example: `button().aria_pressed(true)`
+                    aria_pressed = "aria-pressed": AriaTristate,
+                    aria_autocomplete = "aria-autocomplete": AriaAutocomplete,
+                    aria_current = "aria-current": AriaCurrent,
+                    aria_haspopup = "aria-haspopup": AriaHasPopup,
+                    aria_invalid = "aria-invalid": AriaInvalid,
+                    aria_live = "aria-live": AriaLive,
+                    aria_orientation = "aria-orientation": AriaOrientation,
+                    aria_sort = "aria-sort": AriaSort,
+                );
+            }
+        )+
+    };
+}
+
+implement_aria!(HtmlElement, SvgElement);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{html_element::*, render::Render, svg::*};
+
+    #[test]
+    fn aria_setters_work() {
+        let res = div()
+            .role(Role::Dialog)
+            .aria_modal(true)
+            .aria_labelledby("title")
+            .aria_describedby(["hint", "error"])
+            .add(
+                button()
+                    .aria_expanded(false)
+                    .aria_haspopup(AriaHasPopup::Menu)
+                    .aria_controls("menu")
+                    .aria_pressed(AriaTristate::Mixed),
+            )
+            .add(a().aria_current(AriaCurrent::Page).aria_label("Home"))
+            .add(
+                span()
+                    .role(Role::ProgressBar)
+                    .aria_valuenow(30)
+                    .aria_valuemax(100),
+            )
+            .add(svg().aria_hidden(true))
+            .render();
+        insta::assert_snapshot!(res, @r#"
+        <div role="dialog" aria-modal="true" aria-labelledby="title" aria-describedby="hint error">
+          <button aria-expanded="false" aria-haspopup="menu" aria-controls="menu" aria-pressed="mixed"></button>
+          <a aria-current="page" aria-label="Home"></a>
+          <span role="progressbar" aria-valuenow="30" aria-valuemax="100"></span>
+          <svg xmlns="http://www.w3.org/2000/svg" aria-hidden="true"></svg>
+        </div>
+        "#);
+    }
+}
@@ -0,0 +1,442 @@
+//! `serde` support, serializing nodes as a JSON AST.
+//!
+//! The format is stable:
+//!
+//! - `HtmlNode`: an object with a `type` field:
+//!   - `{"type": "doctype"}`
+//!   - `{"type": "raw", "value": "<!-- x -->"}`
+//!   - `{"type": "text", "value": "Hello"}`
+//!   - `{"type": "element", ..}` with the fields of an `HtmlElement`
+//!   - `{"type": "svg_element", ..}` with the fields of an `SvgElement`
+//!   - `{"type": "fragment", "children": [..]}`
+//!   - `{"type": "prerendered", "value": "<p>Hello</p>", "verbatim_lines": [..], "block": true}`,
+//!     where `verbatim_lines` is left out when empty
+//! - `HtmlElement` and `SvgElement`:
+//!   `{"tag": "div", "attrs": {..}, "classes": [..], "styles": {..}, "children": [..]}`, where
+//!   `attrs`, `classes`, `styles` and `children` are left out when empty and keep their order. The
+//!   tag must be one of `HTML_ELEMENTS` or `SVG_ELEMENTS`, html elements can also be custom
+//!   elements (eg: `x-chart`).
+//! - `AttributeValue`: `null` for `Empty`, a string for `Value` and `{"raw": ".."}` for `Raw`.
+//!
+//! ```
+//! # use fhtmx::prelude::*;
+//! let node = div().id("main").class("card").add("Hello").into_node();
+//! let json = serde_json::to_string(&node).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"type":"element","tag":"div","attrs":{"id":"main"},"classes":["card"],"children":[{"type":"text","value":"Hello"}]}"#
+//! );
+//! let node: HtmlNode = serde_json::from_str(&json).unwrap();
+//! assert_eq!(node.render(), r#"<div class="card" id="main">Hello</div>"#);
+//! ```
+
+use crate::{
+    attribute::AttributeValue,
+    html_element::{HTML_ELEMENTS, HtmlElement, is_valid_custom_element_name},
+    node::HtmlNode,
+    prerender::Prerendered,
+    svg::{SVG_ELEMENTS, SvgElement},
+};
+use indexmap::{IndexMap, IndexSet};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, MapAccess, Visitor},
+    ser::SerializeMap,
+};
+use std::{borrow::Cow, fmt};
+
+type Attrs = IndexMap<Cow<'static, str>, AttributeValue>;
+type Classes = IndexSet<Cow<'static, str>>;
+type Styles = IndexMap<Cow<'static, str>, String>;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NodeRef<'a> {
+    Doctype,
+    Raw {
+        value: &'a str,
+    },
+    Text {
+        value: &'a str,
+    },
+    Element(&'a HtmlElement),
+    SvgElement(&'a SvgElement),
+    Fragment {
+        children: &'a [HtmlNode],
+    },
+    Prerendered {
+        value: &'a str,
+        #[serde(skip_serializing_if = "<[usize]>::is_empty")]
+        verbatim_lines: &'a [usize],
+        block: bool,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NodeRepr {
+    Doctype,
+    Raw {
+        value: String,
+    },
+    Text {
+        value: String,
+    },
+    Element(HtmlElement),
+    SvgElement(SvgElement),
+    Fragment {
+        children: Vec<HtmlNode>,
+    },
+    Prerendered {
+        value: String,
+        #[serde(default)]
+        verbatim_lines: Vec<usize>,
+        block: bool,
+    },
+}
+
+impl Serialize for HtmlNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            HtmlNode::Doctype => NodeRef::Doctype,
+            HtmlNode::Raw(x) => NodeRef::Raw { value: x },
+            HtmlNode::Text(x) => NodeRef::Text { value: x },
+            HtmlNode::Element(x) => NodeRef::Element(x),
+            HtmlNode::SvgElement(x) => NodeRef::SvgElement(x),
+            HtmlNode::Fragment(x) => NodeRef::Fragment { children: x },
+            HtmlNode::Prerendered(x) => NodeRef::Prerendered {
+                value: x.as_str(),
+                verbatim_lines: x.verbatim_lines(),
+                block: x.is_block(),
+            },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HtmlNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match NodeRepr::deserialize(deserializer)? {
+            NodeRepr::Doctype => HtmlNode::Doctype,
+            NodeRepr::Raw { value } => HtmlNode::Raw(value),
+            NodeRepr::Text { value } => HtmlNode::Text(value),
+            NodeRepr::Element(x) => HtmlNode::Element(x),
+            NodeRepr::SvgElement(x) => HtmlNode::SvgElement(x),
+            NodeRepr::Fragment { children } => HtmlNode::Fragment(children),
+            NodeRepr::Prerendered {
+                value,
+                verbatim_lines,
+                block,
+            } => HtmlNode::Prerendered(Prerendered::new(value, verbatim_lines, block)),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct ElementRef<'a> {
+    tag: &'a str,
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    attrs: &'a Attrs,
+    #[serde(skip_serializing_if = "IndexSet::is_empty")]
+    classes: &'a Classes,
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    styles: &'a Styles,
+    #[serde(skip_serializing_if = "<[HtmlNode]>::is_empty")]
+    children: &'a [HtmlNode],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ElementRepr {
+    tag: String,
+    #[serde(default)]
+    attrs: Attrs,
+    #[serde(default)]
+    classes: Classes,
+    #[serde(default)]
+    styles: Styles,
+    #[serde(default)]
+    children: Vec<HtmlNode>,
+}
+
+impl ElementRepr {
+    /// Gets the tag among the `known` ones, or a custom element name when `custom` is set
+    fn tag<E: de::Error>(
+        &mut self,
+        known: &[&'static str],
+        kind: &str,
+        custom: bool,
+    ) -> Result<Cow<'static, str>, E> {
+        if let Some(tag) = known.iter().find(|&&o| o == self.tag) {
+            Ok(Cow::Borrowed(tag))
+        } else if custom && is_valid_custom_element_name(&self.tag) {
+            Ok(Cow::Owned(std::mem::take(&mut self.tag)))
+        } else {
+            Err(E::custom(format!("unknown {kind} tag `{}`", self.tag)))
+        }
+    }
+}
+
+impl Serialize for HtmlElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ElementRef {
+            tag: &self.tag,
+            attrs: &self.attrs,
+            classes: &self.classes,
+            styles: &self.styles,
+            children: &self.children,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HtmlElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut repr = ElementRepr::deserialize(deserializer)?;
+        Ok(HtmlElement {
+            tag: repr.tag(HTML_ELEMENTS, "html", true)?,
+            attrs: repr.attrs,
+            classes: repr.classes,
+            styles: repr.styles,
+            children: repr.children,
+        })
+    }
+}
+
+impl Serialize for SvgElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ElementRef {
+            tag: &self.tag,
+            attrs: &self.attrs,
+            classes: &self.classes,
+            styles: &self.styles,
+            children: &self.children,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SvgElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut repr = ElementRepr::deserialize(deserializer)?;
+        Ok(SvgElement {
+            tag: repr.tag(SVG_ELEMENTS, "svg", false)?,
+            attrs: repr.attrs,
+            classes: repr.classes,
+            styles: repr.styles,
+            children: repr.children,
+        })
+    }
+}
+
+impl Serialize for AttributeValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            AttributeValue::Empty => serializer.serialize_unit(),
+            AttributeValue::Value(x) => serializer.serialize_str(x),
+            AttributeValue::Raw(x) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("raw", x)?;
+                map.end()
+            }
+        }
+    }
+}
+
+struct AttributeValueVisitor;
+
+impl<'de> Visitor<'de> for AttributeValueVisitor {
+    type Value = AttributeValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(r#"null, a string or {"raw": string}"#)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(AttributeValue::Empty)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(AttributeValue::Empty)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(AttributeValue::Value(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(AttributeValue::Value(v))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut raw = None;
+        while let Some(key) = map.next_key::<Cow<'de, str>>()? {
+            if key != "raw" {
+                return Err(de::Error::unknown_field(&key, &["raw"]));
+            }
+            if raw.is_some() {
+                return Err(de::Error::duplicate_field("raw"));
+            }
+            raw = Some(map.next_value::<String>()?);
+        }
+        raw.map(AttributeValue::Raw)
+            .ok_or_else(|| de::Error::missing_field("raw"))
+    }
+}
+
+impl<'de> Deserialize<'de> for AttributeValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AttributeValueVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        element::Element,
+        html_element::*,
+        node::{IntoNode, fragment},
+        render::Render,
+        svg::*,
+    };
+
+    fn page() -> HtmlNode {
+        fragment([
+            HtmlNode::Doctype,
+            div()
+                .id("main")
+                .class("card shadow")
+                .set_empty_attr("hidden")
+                .set_raw_attr("hx-vals", r#"{"a": 1}"#)
+                .add(p().add("Hello <b>"))
+                .add_raw("<!-- x -->")
+                .add(svg().add(svg_path().set_attr("d", "M0 0")))
+                .into_node(),
+        ])
+    }
+
+    #[test]
+    fn serialize_json_ast() {
+        let json = serde_json::to_string_pretty(&page()).unwrap();
+        insta::assert_snapshot!(json, @r#"
+        {
+          "type": "fragment",
+          "children": [
+            {
+              "type": "doctype"
+            },
+            {
+              "type": "element",
+              "tag": "div",
+              "attrs": {
+                "id": "main",
+                "hidden": null,
+                "hx-vals": {
+                  "raw": "{\"a\": 1}"
+                }
+              },
+              "classes": [
+                "card",
+                "shadow"
+              ],
+              "children": [
+                {
+                  "type": "element",
+                  "tag": "p",
+                  "children": [
+                    {
+                      "type": "text",
+                      "value": "Hello <b>"
+                    }
+                  ]
+                },
+                {
+                  "type": "raw",
+                  "value": "<!-- x -->"
+                },
+                {
+                  "type": "svg_element",
+                  "tag": "svg",
+                  "attrs": {
+                    "xmlns": "http://www.w3.org/2000/svg"
+                  },
+                  "children": [
+                    {
+                      "type": "svg_element",
+                      "tag": "path",
+                      "attrs": {
+                        "d": "M0 0"
+                      }
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }
+        "#);
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let node = page();
+        let json = serde_json::to_string(&node).unwrap();
+        let res: HtmlNode = serde_json::from_str(&json).unwrap();
+        assert_eq!(res.render(), node.render());
+        assert_eq!(serde_json::to_string(&res).unwrap(), json);
+
+        let el: HtmlElement =
+            serde_json::from_str(r#"{"tag": "a", "attrs": {"href": "/"}}"#).unwrap();
+        assert_eq!(el.render(), r#"<a href="/"></a>"#);
+
+        let el = custom_element("x-chart").add("Loading");
+        let json = serde_json::to_string(&el).unwrap();
+        let res: HtmlElement = serde_json::from_str(&json).unwrap();
+        assert_eq!(res.render(), "<x-chart>Loading</x-chart>");
+
+        let el = span().style_prop("width", "1px").style_prop("color", "red");
+        let json = serde_json::to_string(&el).unwrap();
+        assert_eq!(
+            json,
+            r#"{"tag":"span","styles":{"width":"1px","color":"red"}}"#
+        );
+        let res: HtmlElement = serde_json::from_str(&json).unwrap();
+        assert_eq!(res.render(), el.render());
+    }
+
+    #[test]
+    fn deserialize_prerendered_sorts_verbatim_lines() {
+        let json = r#"{"type": "prerendered", "value": "<pre>a\nb\nc</pre>", "verbatim_lines": [2, 1, 2], "block": true}"#;
+        let node: HtmlNode = serde_json::from_str(json).unwrap();
+        let HtmlNode::Prerendered(x) = &node else {
+            panic!("expected a prerendered node");
+        };
+        assert_eq!(x.verbatim_lines(), [1, 2]);
+        assert_eq!(x.options(), None);
+        insta::assert_snapshot!(div().add(node).render(), @r"
+        <div>
+          <pre>a
+        b
+        c</pre>
+        </div>
+        ");
+    }
+
+    #[test]
+    fn deserialize_errors() {
+        let err = |json: &str| {
+            serde_json::from_str::<HtmlNode>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        insta::assert_snapshot!(err(r#"{"type": "element", "tag": "blink"}"#), @"unknown html tag `blink`");
+        insta::assert_snapshot!(err(r#"{"type": "svg_element", "tag": "div"}"#), @"unknown svg tag `div`");
+        insta::assert_snapshot!(err(r#"{"type": "svg_element", "tag": "x-chart"}"#), @"unknown svg tag `x-chart`");
+        insta::assert_snapshot!(err(r#"{"type": "comment"}"#), @"unknown variant `comment`, expected one of `doctype`, `raw`, `text`, `element`, `svg_element`, `fragment`, `prerendered` at line 1 column 18");
+        insta::assert_snapshot!(
+            err(r#"{"type": "element", "tag": "a", "attrs": {"href": 1}}"#),
+            @r#"invalid type: integer `1`, expected null, a string or {"raw": string}"#
+        );
+    }
+}
@@ -1,4 +1,13 @@
-use crate::utils::escape_html_to;
+use crate::utils::write_escaped_html;
+use std::{
+    borrow::Cow,
+    fmt,
+    num::{
+        NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize, NonZeroU8,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+    },
+};
+use uuid::Uuid;
 
 // TODO: use Cow here when rust gets the specialization feature
 #[derive(Clone, Debug)]
@@ -24,29 +33,68 @@ impl AttributeValue {
     }
 
     pub fn render_to(&self, buf: &mut String) {
+        // Writing to a `String` never fails
+        let _ = self.write_to(buf);
+    }
+
+    /// Renders the attribute value (including the `=` and quotes) into a writer
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
         match self {
-            AttributeValue::Empty => {}
+            AttributeValue::Empty => Ok(()),
             AttributeValue::Raw(v) => {
-                let has_double_quote = v.contains('\"');
-                if has_double_quote {
-                    buf.push_str("='");
-                } else {
-                    buf.push_str("=\"");
-                }
-                buf.push_str(v);
-                if has_double_quote {
-                    buf.push('\'');
+                let quote = if v.contains('"') && !v.contains('\'') {
+                    '\''
                 } else {
-                    buf.push('"');
-                }
+                    '"'
+                };
+                w.write_char('=')?;
+                w.write_char(quote)?;
+                write_raw_attr_value(v, quote, w)?;
+                w.write_char(quote)
             }
             AttributeValue::Value(v) => {
-                buf.push_str("=\"");
-                escape_html_to(v, buf);
-                buf.push('"');
+                w.write_str("=\"")?;
+                write_escaped_html(v, w)?;
+                w.write_char('"')
             }
         }
     }
+
+    /// Renders the attribute value as XML requires: always with a value (`=""` for `Empty`) and
+    /// escaping every special character, also in raw values
+    pub fn write_xml_to<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+        let v = match self {
+            AttributeValue::Empty => "",
+            AttributeValue::Raw(v) | AttributeValue::Value(v) => v,
+        };
+        w.write_str("=\"")?;
+        write_escaped_html(v, w)?;
+        w.write_char('"')
+    }
+}
+
+/// Writes a raw attribute value keeping it as readable as possible: only the `quote` character
+/// and the `&` that would start a character reference (eg: `&amp;`, `&#39;`) are escaped.
+fn write_raw_attr_value<W: fmt::Write + ?Sized>(v: &str, quote: char, w: &mut W) -> fmt::Result {
+    let mut last = 0;
+    for (i, c) in v.char_indices() {
+        let escaped = match c {
+            '"' if quote == '"' => "&quot;",
+            '\'' if quote == '\'' => "&#39;",
+            '&' if v[i + 1..]
+                .bytes()
+                .next()
+                .is_some_and(|o| o == b'#' || o.is_ascii_alphanumeric()) =>
+            {
+                "&amp;"
+            }
+            _ => continue,
+        };
+        w.write_str(&v[last..i])?;
+        w.write_str(escaped)?;
+        last = i + 1;
+    }
+    w.write_str(&v[last..])
 }
 
 pub trait IntoAttributeValue: Sized {
@@ -74,6 +122,57 @@ impl IntoAttributeValue for bool {
     }
 }
 
+/// `None` leaves the attribute out
+impl<T: IntoAttributeValue> IntoAttributeValue for Option<T> {
+    fn into_attr(self) -> Option<AttributeValue> {
+        self.and_then(|x| x.into_attr())
+    }
+}
+
+impl IntoAttributeValue for Cow<'_, str> {
+    fn into_attr(self) -> Option<AttributeValue> {
+        Some(AttributeValue::Value(self.into_owned()))
+    }
+}
+
+/// Joins values into a space separated token list (eg: for `rel`, `sizes` or `accept`). Values
+/// that are left out (`None`, `false`) or empty are skipped, and an empty list leaves the attribute
+/// out.
+fn token_list(values: impl IntoIterator<Item = impl IntoAttributeValue>) -> Option<AttributeValue> {
+    let mut res = String::new();
+    for v in values.into_iter().filter_map(|o| o.into_attr()) {
+        let (AttributeValue::Value(v) | AttributeValue::Raw(v)) = v else {
+            continue;
+        };
+        if v.is_empty() {
+            continue;
+        }
+        if !res.is_empty() {
+            res.push(' ');
+        }
+        res.push_str(&v);
+    }
+    (!res.is_empty()).then_some(AttributeValue::Value(res))
+}
+
+impl<T: IntoAttributeValue> IntoAttributeValue for Vec<T> {
+    fn into_attr(self) -> Option<AttributeValue> {
+        token_list(self)
+    }
+}
+
+impl<T: IntoAttributeValue, const N: usize> IntoAttributeValue for [T; N] {
+    fn into_attr(self) -> Option<AttributeValue> {
+        token_list(self)
+    }
+}
+
+impl<T: IntoAttributeValue + Clone> IntoAttributeValue for &[T] {
+    fn into_attr(self) -> Option<AttributeValue> {
+        token_list(self.iter().cloned())
+    }
+}
+
 macro_rules! implement_for_display {
     ($($t:ty),* $(,)?) => {
         $(
@@ -87,6 +186,285 @@ macro_rules! implement_for_display {
 }
 
 implement_for_display!(
-    char, &str, &String, String, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize,
-    f32, f64
+    char,
+    &str,
+    &String,
+    String,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    Uuid,
+    url::Url,
+    &url::Url,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize
 );
+
+/// Formats a valid html time string (eg: `09:30:00` or `09:30:00.250`), keeping up to
+/// milliseconds
+#[cfg(any(feature = "chrono_0_4", feature = "jiff_0_2"))]
+fn html_time(hour: u32, minute: u32, second: u32, nanos: u32) -> String {
+    let millis = nanos / 1_000_000;
+    if millis == 0 {
+        format!("{hour:02}:{minute:02}:{second:02}")
+    } else {
+        format!("{hour:02}:{minute:02}:{second:02}.{millis:03}")
+    }
+}
+
+/// Dates and times are formatted as the values expected by `input` and `time` elements:
+/// `2024-01-31`, `09:30:00`, `2024-01-31T09:30:00` and `2024-01-31T09:30:00Z` (in UTC)
+#[cfg(feature = "chrono_0_4")]
+mod chrono_impls {
+    use super::*;
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+
+    fn time(t: NaiveTime) -> String {
+        // Chrono keeps leap seconds in the nanoseconds, but html times can't have a `:60` second
+        // so they are truncated to the end of the previous one
+        html_time(
+            t.hour(),
+            t.minute(),
+            t.second(),
+            t.nanosecond().min(999_999_999),
+        )
+    }
+
+    impl IntoAttributeValue for NaiveDate {
+        fn into_attr(self) -> Option<AttributeValue> {
+            Some(AttributeValue::Value(self.format("%Y-%m-%d").to_string()))
+        }
+    }
+
+    impl IntoAttributeValue for NaiveTime {
+        fn into_attr(self) -> Option<AttributeValue> {
+            Some(AttributeValue::Value(time(self)))
+        }
+    }
+
+    impl IntoAttributeValue for NaiveDateTime {
+        fn into_attr(self) -> Option<AttributeValue> {
+            let date = self.format("%Y-%m-%d");
+            Some(AttributeValue::Value(format!(
+                "{date}T{}",
+                time(self.time())
+            )))
+        }
+    }
+
+    impl<Tz: TimeZone> IntoAttributeValue for DateTime<Tz> {
+        fn into_attr(self) -> Option<AttributeValue> {
+            let utc = self.naive_utc();
+            let date = utc.format("%Y-%m-%d");
+            Some(AttributeValue::Value(format!(
+                "{date}T{}Z",
+                time(utc.time())
+            )))
+        }
+    }
+}
+
+/// Dates and times are formatted as the values expected by `input` and `time` elements:
+/// `2024-01-31`, `09:30:00`, `2024-01-31T09:30:00` and `2024-01-31T09:30:00Z` (in UTC)
+#[cfg(feature = "jiff_0_2")]
+mod jiff_impls {
+    use super::*;
+    use jiff::{
+        Timestamp, Zoned,
+        civil::{Date, DateTime, Time},
+        tz::Offset,
+    };
+
+    fn time(t: Time) -> String {
+        html_time(
+            t.hour() as u32,
+            t.minute() as u32,
+            t.second() as u32,
+            t.subsec_nanosecond() as u32,
+        )
+    }
+
+    fn datetime(dt: DateTime) -> String {
+        format!("{}T{}", dt.date(), time(dt.time()))
+    }
+
+    impl IntoAttributeValue for Date {
+        fn into_attr(self) -> Option<AttributeValue> {
+            Some(AttributeValue::Value(self.to_string()))
+        }
+    }
+
+    impl IntoAttributeValue for Time {
+        fn into_attr(self) -> Option<AttributeValue> {
+            Some(AttributeValue::Value(time(self)))
+        }
+    }
+
+    impl IntoAttributeValue for DateTime {
+        fn into_attr(self) -> Option<AttributeValue> {
+            Some(AttributeValue::Value(datetime(self)))
+        }
+    }
+
+    impl IntoAttributeValue for Timestamp {
+        fn into_attr(self) -> Option<AttributeValue> {
+            let utc = Offset::UTC.to_datetime(self);
+            Some(AttributeValue::Value(format!("{}Z", datetime(utc))))
+        }
+    }
+
+    impl IntoAttributeValue for &Zoned {
+        fn into_attr(self) -> Option<AttributeValue> {
+            self.timestamp().into_attr()
+        }
+    }
+
+    impl IntoAttributeValue for Zoned {
+        fn into_attr(self) -> Option<AttributeValue> {
+            self.timestamp().into_attr()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        element::Element, html_element::*, node::HtmlNode, render::Render, utils::unescape_html,
+    };
+
+    fn raw(v: &str) -> String {
+        let mut res = String::new();
+        AttributeValue::Raw(v.to_string()).render_to(&mut res);
+        res
+    }
+
+    #[test]
+    fn raw_attr_quoting() {
+        insta::assert_snapshot!(raw("js:{x: 1}"), @r#"="js:{x: 1}""#);
+        insta::assert_snapshot!(raw(r#"{"id": 1}"#), @r#"='{"id": 1}'"#);
+        insta::assert_snapshot!(raw("alert('hi')"), @r#"="alert('hi')""#);
+        insta::assert_snapshot!(raw(r#"{"name": "O'Brien"}"#), @r#"="{&quot;name&quot;: &quot;O'Brien&quot;}""#);
+        insta::assert_snapshot!(raw("a && b & c"), @r#"="a && b & c""#);
+        insta::assert_snapshot!(raw("x &amp; &#39; &copy y&"), @r#"="x &amp;amp; &amp;#39; &amp;copy y&""#);
+    }
+
+    #[test]
+    fn raw_attr_user_data() {
+        let name = r#"O'Brien" onclick="alert(1)"#;
+        let vals = format!(r#"{{"name": "{}"}}"#, name.replace('"', r#"\""#));
+        let res = button().hx_vals(&vals).add("Save").render();
+        insta::assert_snapshot!(res, @r#"<button hx-vals="{&quot;name&quot;: &quot;O'Brien\&quot; onclick=\&quot;alert(1)&quot;}">Save</button>"#);
+
+        // The browser gets back the original value
+        let parsed = HtmlNode::parse_fragment(&res).unwrap();
+        let attrs = parsed.as_element().unwrap().attrs();
+        let Some(AttributeValue::Value(parsed_vals)) = attrs.get("hx-vals") else {
+            panic!("missing hx-vals");
+        };
+        assert_eq!(*parsed_vals, vals);
+        for v in [r#"a'b"c"#, "&amp;&&#x41;'\"", "&nbsp &lt;"] {
+            let rendered = raw(v);
+            assert_eq!(unescape_html(&rendered[2..rendered.len() - 1]), v);
+        }
+    }
+
+    #[test]
+    fn into_attr_for_options_and_collections() {
+        let id = Uuid::nil();
+        let url = url::Url::parse("https://example.com/a?b=c&d").unwrap();
+        let res = div()
+            .id(id)
+            .set_attr("data-none", None::<&str>)
+            .set_attr("data-some", Some(2))
+            .set_attr("data-cow", Cow::Borrowed("cow"))
+            .set_attr("data-count", NonZeroU32::new(3).unwrap())
+            .add(
+                a().rel(["noopener", "noreferrer"])
+                    .set_attr("data-url", &url),
+            )
+            .add(input().accept(vec!["image/png", "", "image/jpeg"]))
+            .add(img().sizes(&["(max-width: 600px) 480px", "800px"][..]))
+            .add(link().rel(Vec::<String>::new()))
+            .render();
+        insta::assert_snapshot!(res, @r#"
+        <div id="00000000-0000-0000-0000-000000000000" data-some="2" data-cow="cow" data-count="3">
+          <a rel="noopener noreferrer" data-url="https://example.com/a?b=c&amp;d"></a>
+          <input accept="image/png image/jpeg" />
+          <img sizes="(max-width: 600px) 480px 800px" />
+          <link />
+        </div>
+        "#);
+    }
+
+    #[cfg(feature = "chrono_0_4")]
+    #[test]
+    fn into_attr_for_chrono() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let dt = date.and_hms_milli_opt(9, 5, 7, 250).unwrap();
+        let utc = Utc.from_utc_datetime(&date.and_hms_opt(23, 0, 0).unwrap());
+        let offset = utc.with_timezone(&chrono::FixedOffset::east_opt(5 * 3600).unwrap());
+        let value = |v: AttributeValue| match v {
+            AttributeValue::Value(x) => x,
+            _ => unreachable!(),
+        };
+        assert_eq!(value(date.into_attr().unwrap()), "2024-01-31");
+        assert_eq!(value(dt.time().into_attr().unwrap()), "09:05:07.250");
+        let leap = chrono::NaiveTime::from_hms_milli_opt(23, 59, 59, 1_500).unwrap();
+        assert_eq!(value(leap.into_attr().unwrap()), "23:59:59.999");
+        assert_eq!(value(dt.into_attr().unwrap()), "2024-01-31T09:05:07.250");
+        assert_eq!(value(utc.into_attr().unwrap()), "2024-01-31T23:00:00Z");
+        assert_eq!(value(offset.into_attr().unwrap()), "2024-01-31T23:00:00Z");
+        let res = input().set_attr("type", "date").value(date).render();
+        insta::assert_snapshot!(res, @r#"<input type="date" value="2024-01-31" />"#);
+    }
+
+    #[cfg(feature = "jiff_0_2")]
+    #[test]
+    fn into_attr_for_jiff() {
+        use jiff::{Timestamp, civil};
+
+        let dt = civil::date(2024, 1, 31).at(9, 5, 7, 250_123_456);
+        let ts: Timestamp = "2024-01-31T23:00:00.5-05:00".parse().unwrap();
+        let value = |v: AttributeValue| match v {
+            AttributeValue::Value(x) => x,
+            _ => unreachable!(),
+        };
+        assert_eq!(value(dt.date().into_attr().unwrap()), "2024-01-31");
+        assert_eq!(value(dt.time().into_attr().unwrap()), "09:05:07.250");
+        let leap = chrono::NaiveTime::from_hms_milli_opt(23, 59, 59, 1_500).unwrap();
+        assert_eq!(value(leap.into_attr().unwrap()), "23:59:59.999");
+        assert_eq!(value(dt.into_attr().unwrap()), "2024-01-31T09:05:07.250");
+        assert_eq!(value(ts.into_attr().unwrap()), "2024-02-01T04:00:00.500Z");
+        let zoned = ts.to_zoned(jiff::tz::TimeZone::UTC);
+        assert_eq!(
+            value((&zoned).into_attr().unwrap()),
+            "2024-02-01T04:00:00.500Z"
+        );
+        let res = time().datetime(dt.date()).add("Jan 31").render();
+        insta::assert_snapshot!(res, @r#"<time datetime="2024-01-31">Jan 31</time>"#);
+    }
+}
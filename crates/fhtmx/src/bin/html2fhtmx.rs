@@ -0,0 +1,30 @@
+//! Converts html into fhtmx builder code.
+//!
+//! Usage: `html2fhtmx [FILE]`, reads from stdin when no file is given.
+
+use fhtmx::prelude::html_to_fhtmx;
+use std::io::Read;
+
+fn main() {
+    let html = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Failed to read {path}: {e}");
+            std::process::exit(1);
+        }),
+        None => {
+            let mut buf = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut buf) {
+                eprintln!("Failed to read stdin: {e}");
+                std::process::exit(1);
+            }
+            buf
+        }
+    };
+    match html_to_fhtmx(&html) {
+        Ok(code) => println!("{code}"),
+        Err(e) => {
+            eprintln!("Failed to parse html: {e}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,571 @@
+//! Tailwind aware class merging (like `tailwind-merge`), used by `Element::merge_class`.
+//!
+//! Classes are grouped by the css property they set (`p-*`, `px-*`, `bg-<color>`, `text-<size>`,
+//! `flex`/`hidden`, ...), per variant (`hover:`, `lg:`, `dark:`, `!` important, in any order). A
+//! class replaces the earlier ones of its group and of the groups it covers: `p-2` replaces `p-4`
+//! and `px-4`, while `px-2` only replaces `px-4`, `pl-*` and `pr-*`. Classes outside of the known
+//! groups (eg: daisyUI components like `btn-primary`) are kept as they are.
+
+use indexmap::IndexSet;
+use std::borrow::Cow;
+
+/// Utilities matched by their whole name
+const EXACT_UTILITIES: &[(&str, &str)] = &[
+    ("block", "display"),
+    ("inline-block", "display"),
+    ("inline", "display"),
+    ("flex", "display"),
+    ("inline-flex", "display"),
+    ("grid", "display"),
+    ("inline-grid", "display"),
+    ("contents", "display"),
+    ("flow-root", "display"),
+    ("list-item", "display"),
+    ("hidden", "display"),
+    ("static", "position"),
+    ("fixed", "position"),
+    ("absolute", "position"),
+    ("relative", "position"),
+    ("sticky", "position"),
+    ("visible", "visibility"),
+    ("invisible", "visibility"),
+    ("italic", "font-style"),
+    ("not-italic", "font-style"),
+    ("underline", "text-decoration"),
+    ("overline", "text-decoration"),
+    ("line-through", "text-decoration"),
+    ("no-underline", "text-decoration"),
+    ("uppercase", "text-transform"),
+    ("lowercase", "text-transform"),
+    ("capitalize", "text-transform"),
+    ("normal-case", "text-transform"),
+    ("truncate", "text-overflow"),
+    ("grow", "grow"),
+    ("shrink", "shrink"),
+    ("border", "border-w"),
+    ("border-x", "border-w-x"),
+    ("border-y", "border-w-y"),
+    ("border-s", "border-w-s"),
+    ("border-e", "border-w-e"),
+    ("border-t", "border-w-t"),
+    ("border-r", "border-w-r"),
+    ("border-b", "border-w-b"),
+    ("border-l", "border-w-l"),
+    ("rounded", "rounded"),
+    ("rounded-t", "rounded-t"),
+    ("rounded-r", "rounded-r"),
+    ("rounded-b", "rounded-b"),
+    ("rounded-l", "rounded-l"),
+    ("rounded-s", "rounded-s"),
+    ("rounded-e", "rounded-e"),
+    ("rounded-tl", "rounded-tl"),
+    ("rounded-tr", "rounded-tr"),
+    ("rounded-br", "rounded-br"),
+    ("rounded-bl", "rounded-bl"),
+    ("rounded-ss", "rounded-ss"),
+    ("rounded-se", "rounded-se"),
+    ("rounded-es", "rounded-es"),
+    ("rounded-ee", "rounded-ee"),
+    ("shadow", "shadow"),
+    ("ring", "ring-w"),
+    ("outline", "outline-w"),
+    ("transition", "transition"),
+];
+
+/// Utilities taking a value (`<prefix>-<value>`)
+const PREFIXES: &[&str] = &[
+    "animate",
+    "aspect",
+    "basis",
+    "bg",
+    "border",
+    "border-b",
+    "border-e",
+    "border-l",
+    "border-r",
+    "border-s",
+    "border-t",
+    "border-x",
+    "border-y",
+    "bottom",
+    "col-end",
+    "col-span",
+    "col-start",
+    "content",
+    "cursor",
+    "decoration",
+    "delay",
+    "duration",
+    "ease",
+    "end",
+    "flex",
+    "font",
+    "gap",
+    "gap-x",
+    "gap-y",
+    "grid-cols",
+    "grid-rows",
+    "grow",
+    "h",
+    "inset",
+    "inset-x",
+    "inset-y",
+    "items",
+    "justify",
+    "leading",
+    "left",
+    "m",
+    "max-h",
+    "max-w",
+    "mb",
+    "me",
+    "min-h",
+    "min-w",
+    "ml",
+    "mr",
+    "ms",
+    "mt",
+    "mx",
+    "my",
+    "object",
+    "opacity",
+    "order",
+    "outline",
+    "outline-offset",
+    "overflow",
+    "overflow-x",
+    "overflow-y",
+    "p",
+    "pb",
+    "pe",
+    "pl",
+    "place-content",
+    "place-items",
+    "place-self",
+    "pointer-events",
+    "pr",
+    "ps",
+    "pt",
+    "px",
+    "py",
+    "right",
+    "ring",
+    "rotate",
+    "rounded",
+    "rounded-b",
+    "rounded-bl",
+    "rounded-br",
+    "rounded-e",
+    "rounded-ee",
+    "rounded-es",
+    "rounded-l",
+    "rounded-r",
+    "rounded-s",
+    "rounded-se",
+    "rounded-ss",
+    "rounded-t",
+    "rounded-tl",
+    "rounded-tr",
+    "row-end",
+    "row-span",
+    "row-start",
+    "scale",
+    "self",
+    "shadow",
+    "shrink",
+    "size",
+    "space-x",
+    "space-y",
+    "start",
+    "stroke",
+    "text",
+    "top",
+    "tracking",
+    "translate-x",
+    "translate-y",
+    "w",
+    "whitespace",
+    "z",
+];
+
+/// Groups replaced by a later class of the group on the left
+const CONFLICTS: &[(&str, &[&str])] = &[
+    ("p", &["px", "py", "ps", "pe", "pt", "pr", "pb", "pl"]),
+    ("px", &["pr", "pl"]),
+    ("py", &["pt", "pb"]),
+    ("m", &["mx", "my", "ms", "me", "mt", "mr", "mb", "ml"]),
+    ("mx", &["mr", "ml"]),
+    ("my", &["mt", "mb"]),
+    ("gap", &["gap-x", "gap-y"]),
+    ("size", &["w", "h"]),
+    (
+        "inset",
+        &[
+            "inset-x", "inset-y", "top", "right", "bottom", "left", "start", "end",
+        ],
+    ),
+    ("inset-x", &["right", "left"]),
+    ("inset-y", &["top", "bottom"]),
+    ("overflow", &["overflow-x", "overflow-y"]),
+    ("font-size", &["leading"]),
+    (
+        "rounded",
+        &[
+            "rounded-t",
+            "rounded-r",
+            "rounded-b",
+            "rounded-l",
+            "rounded-s",
+            "rounded-e",
+            "rounded-tl",
+            "rounded-tr",
+            "rounded-br",
+            "rounded-bl",
+            "rounded-ss",
+            "rounded-se",
+            "rounded-es",
+            "rounded-ee",
+        ],
+    ),
+    ("rounded-t", &["rounded-tl", "rounded-tr"]),
+    ("rounded-r", &["rounded-tr", "rounded-br"]),
+    ("rounded-b", &["rounded-br", "rounded-bl"]),
+    ("rounded-l", &["rounded-tl", "rounded-bl"]),
+    ("rounded-s", &["rounded-ss", "rounded-es"]),
+    ("rounded-e", &["rounded-se", "rounded-ee"]),
+    (
+        "border-w",
+        &[
+            "border-w-x",
+            "border-w-y",
+            "border-w-s",
+            "border-w-e",
+            "border-w-t",
+            "border-w-r",
+            "border-w-b",
+            "border-w-l",
+        ],
+    ),
+    ("border-w-x", &["border-w-r", "border-w-l"]),
+    ("border-w-y", &["border-w-t", "border-w-b"]),
+    (
+        "border-color",
+        &[
+            "border-color-x",
+            "border-color-y",
+            "border-color-s",
+            "border-color-e",
+            "border-color-t",
+            "border-color-r",
+            "border-color-b",
+            "border-color-l",
+        ],
+    ),
+    ("border-color-x", &["border-color-r", "border-color-l"]),
+    ("border-color-y", &["border-color-t", "border-color-b"]),
+];
+
+const FONT_SIZES: &[&str] = &[
+    "xs", "sm", "base", "lg", "xl", "2xl", "3xl", "4xl", "5xl", "6xl", "7xl", "8xl", "9xl",
+];
+
+const FONT_WEIGHTS: &[&str] = &[
+    "thin",
+    "extralight",
+    "light",
+    "normal",
+    "medium",
+    "semibold",
+    "bold",
+    "extrabold",
+    "black",
+];
+
+const SHADOW_SIZES: &[&str] = &["2xs", "xs", "sm", "md", "lg", "xl", "2xl", "none", "inner"];
+
+const LINE_STYLES: &[&str] = &["solid", "dashed", "dotted", "double", "hidden", "none"];
+
+/// Splits `class` into its variants (sorted, with `!` when important) and its utility
+pub(crate) fn split_variants(class: &str) -> (String, &str) {
+    let mut variants = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in class.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            ':' if depth == 0 => {
+                variants.push(&class[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let mut utility = &class[start..];
+    let important = if let Some(x) = utility.strip_prefix('!') {
+        utility = x;
+        true
+    } else if let Some(x) = utility.strip_suffix('!') {
+        utility = x;
+        true
+    } else {
+        false
+    };
+    variants.sort_unstable();
+    let mut key = variants.join(":");
+    if important {
+        key.push('!');
+    }
+    (key, utility)
+}
+
+fn is_number(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|o| o.is_ascii_digit())
+}
+
+/// Checks if `value` is an arbitrary length (eg: `[3px]`, `[length:var(--x)]`)
+fn is_arbitrary_length(value: &str) -> bool {
+    value
+        .strip_prefix('[')
+        .and_then(|o| o.strip_suffix(']'))
+        .is_some_and(|o| {
+            o.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+                || o.starts_with("length:")
+                || o.starts_with("calc(")
+        })
+}
+
+/// Gets the group of a utility (without variants), `None` if it's not a known Tailwind utility
+fn utility_group(utility: &str) -> Option<Cow<'static, str>> {
+    let utility = utility.strip_prefix('-').unwrap_or(utility);
+    if let Some((_, group)) = EXACT_UTILITIES.iter().find(|(o, _)| *o == utility) {
+        return Some(Cow::Borrowed(group));
+    }
+    // Arbitrary properties (eg: `[mask-type:luminance]`)
+    if let Some(property) = utility
+        .strip_prefix('[')
+        .and_then(|o| o.split_once(':'))
+        .map(|(o, _)| o)
+    {
+        return Some(format!("[{property}]").into());
+    }
+    let (prefix, value) = PREFIXES
+        .iter()
+        .filter_map(|&o| Some((o, utility.strip_prefix(o)?.strip_prefix('-')?)))
+        .max_by_key(|(o, _)| o.len())?;
+    if value.is_empty() {
+        return None;
+    }
+    // Modifiers like `/50` in `bg-red-500/50` or `text-sm/6` don't change the group
+    let base = match value.rsplit_once('/') {
+        Some((o, _)) if !value.ends_with(']') => o,
+        _ => value,
+    };
+    let group = match prefix {
+        "text" => match base {
+            _ if FONT_SIZES.contains(&base) || is_arbitrary_length(base) => "font-size",
+            "left" | "center" | "right" | "justify" | "start" | "end" => "text-align",
+            "wrap" | "nowrap" | "balance" | "pretty" => "text-wrap",
+            "ellipsis" | "clip" => "text-overflow",
+            _ => "text-color",
+        },
+        "font" if FONT_WEIGHTS.contains(&base) || is_number(base) => "font-weight",
+        "font" => "font-family",
+        "flex" => match base {
+            "row" | "row-reverse" | "col" | "col-reverse" => "flex-direction",
+            "wrap" | "wrap-reverse" | "nowrap" => "flex-wrap",
+            _ => "flex",
+        },
+        "bg" => match base {
+            "fixed" | "local" | "scroll" => "bg-attachment",
+            "bottom" | "center" | "left" | "left-bottom" | "left-top" | "right"
+            | "right-bottom" | "right-top" | "top" => "bg-position",
+            "auto" | "cover" | "contain" => "bg-size",
+            "none" => "bg-image",
+            _ if base.starts_with("repeat") || base == "no-repeat" => "bg-repeat",
+            _ if base.starts_with("clip-") => "bg-clip",
+            _ if base.starts_with("origin-") => "bg-origin",
+            _ if base.starts_with("blend-") => "bg-blend",
+            _ if ["gradient-", "linear-", "radial-", "conic-", "[url("]
+                .iter()
+                .any(|o| base.starts_with(o)) =>
+            {
+                "bg-image"
+            }
+            _ => "bg-color",
+        },
+        "border" if LINE_STYLES.contains(&base) => "border-style",
+        "border" | "border-x" | "border-y" | "border-s" | "border-e" | "border-t" | "border-r"
+        | "border-b" | "border-l" => {
+            let side = prefix.strip_prefix("border").unwrap_or_default();
+            let kind = if is_number(base) || is_arbitrary_length(base) {
+                "border-w"
+            } else {
+                "border-color"
+            };
+            return Some(format!("{kind}{side}").into());
+        }
+        "shadow" if SHADOW_SIZES.contains(&base) || base.starts_with('[') => "shadow",
+        "shadow" => "shadow-color",
+        "ring" if is_number(base) || is_arbitrary_length(base) => "ring-w",
+        "ring" if base == "inset" => "ring-inset",
+        "ring" => "ring-color",
+        "outline" if is_number(base) || is_arbitrary_length(base) => "outline-w",
+        "outline" if LINE_STYLES.contains(&base) => "outline-style",
+        "outline" => "outline-color",
+        "decoration" => match base {
+            "solid" | "double" | "dotted" | "dashed" | "wavy" => "decoration-style",
+            "auto" | "from-font" => "decoration-thickness",
+            _ if is_number(base) || is_arbitrary_length(base) => "decoration-thickness",
+            _ => "decoration-color",
+        },
+        "stroke" if is_number(base) || is_arbitrary_length(base) => "stroke-w",
+        "stroke" => "stroke-color",
+        _ => prefix,
+    };
+    Some(Cow::Borrowed(group))
+}
+
+/// Gets the variants and group of a class, `None` if it's not a known Tailwind utility
+fn class_key(class: &str) -> Option<(String, Cow<'static, str>)> {
+    let (variants, utility) = split_variants(class);
+    Some((variants, utility_group(utility)?))
+}
+
+/// Adds `class` to `classes`, removing the earlier classes it overrides
+pub(crate) fn merge_class_into(
+    classes: &mut IndexSet<Cow<'static, str>>,
+    class: Cow<'static, str>,
+) {
+    if let Some((variants, group)) = class_key(&class) {
+        let covered = CONFLICTS
+            .iter()
+            .find(|(o, _)| *o == group)
+            .map(|(_, o)| *o)
+            .unwrap_or_default();
+        classes.retain(|o| match class_key(o) {
+            Some((v, g)) => v != variants || (g != group && !covered.contains(&g.as_ref())),
+            None => true,
+        });
+    }
+    classes.insert(class);
+}
+
+/// Merges a list of Tailwind classes, dropping the ones overridden by a later class.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let res = tw_merge("px-2 py-1 bg-red-500 hover:bg-red-700 p-3 bg-blue-500/50 btn btn-primary");
+/// assert_eq!(res, "hover:bg-red-700 p-3 bg-blue-500/50 btn btn-primary");
+/// ```
+pub fn tw_merge(classes: &str) -> String {
+    let mut res = IndexSet::new();
+    for class in classes.split_whitespace() {
+        merge_class_into(&mut res, Cow::Owned(class.to_string()));
+    }
+    res.into_iter().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{components::*, element::Element, html_element::*, render::Render};
+
+    #[test]
+    fn utility_groups() {
+        let cases = [
+            ("p-4", Some("p")),
+            ("-mt-2", Some("mt")),
+            ("gap-x-2", Some("gap-x")),
+            ("max-w-lg", Some("max-w")),
+            ("text-sm/6", Some("font-size")),
+            ("text-[14px]", Some("font-size")),
+            ("text-[#fff]", Some("text-color")),
+            ("text-base-content", Some("text-color")),
+            ("text-center", Some("text-align")),
+            ("font-bold", Some("font-weight")),
+            ("font-mono", Some("font-family")),
+            ("flex", Some("display")),
+            ("flex-col", Some("flex-direction")),
+            ("flex-1", Some("flex")),
+            ("bg-base-100", Some("bg-color")),
+            ("bg-cover", Some("bg-size")),
+            ("bg-linear-to-r", Some("bg-image")),
+            ("border", Some("border-w")),
+            ("border-t-2", Some("border-w-t")),
+            ("border-l-primary/50", Some("border-color-l")),
+            ("border-dashed", Some("border-style")),
+            ("rounded-tl-lg", Some("rounded-tl")),
+            ("border-t", Some("border-w-t")),
+            ("border-x", Some("border-w-x")),
+            ("rounded-t", Some("rounded-t")),
+            ("rounded-ee", Some("rounded-ee")),
+            ("shadow-lg", Some("shadow")),
+            ("shadow-black/20", Some("shadow-color")),
+            ("[mask-type:luminance]", Some("[mask-type]")),
+            ("btn-primary", None),
+            ("table", None),
+            ("collapse", None),
+        ];
+        for (class, group) in cases {
+            assert_eq!(utility_group(class).as_deref(), group, "{class}");
+        }
+    }
+
+    #[test]
+    fn tw_merge_works() {
+        let cases = [
+            ("p-4 p-2", "p-2"),
+            ("px-4 py-1 p-2", "p-2"),
+            ("p-4 px-2", "p-4 px-2"),
+            ("pl-1 pr-1 px-2", "px-2"),
+            (
+                "hover:bg-red-500 bg-blue-500 hover:bg-green-500",
+                "bg-blue-500 hover:bg-green-500",
+            ),
+            ("lg:hover:p-2 hover:lg:p-4", "hover:lg:p-4"),
+            ("!p-2 p-4", "!p-2 p-4"),
+            (
+                "text-red-500 text-lg text-blue-500",
+                "text-lg text-blue-500",
+            ),
+            ("leading-6 text-sm", "text-sm"),
+            ("block flex hidden", "hidden"),
+            (
+                "border border-2 border-red-500 border-t-4",
+                "border-2 border-red-500 border-t-4",
+            ),
+            ("rounded-tl-lg rounded-t-none rounded-xl", "rounded-xl"),
+            ("rounded-lg rounded-t", "rounded-lg rounded-t"),
+            ("rounded-tl-lg rounded-t", "rounded-t"),
+            (
+                "border-t-4 border-red-500 border-t",
+                "border-red-500 border-t",
+            ),
+            ("w-4 h-4 size-8", "size-8"),
+            (
+                "btn btn-primary btn-secondary",
+                "btn btn-primary btn-secondary",
+            ),
+        ];
+        for (classes, expected) in cases {
+            assert_eq!(tw_merge(classes), expected, "{classes}");
+        }
+    }
+
+    #[test]
+    fn merge_class_overrides_components() {
+        let res = div()
+            .class("p-4 bg-base-100 md:p-6")
+            .merge_class("p-2 bg-primary/10")
+            .merge_class("md:px-3")
+            .render();
+        insta::assert_snapshot!(res, @r#"<div class="md:p-6 p-2 bg-primary/10 md:px-3"></div>"#);
+
+        let el =
+            mk_callout("Note", "content", "info", false).merge_class("border-l-2 rounded-none");
+        insta::assert_snapshot!(el.classes().iter().cloned().collect::<Vec<_>>().join(" "), @"collapse collapse-open border bg-base-100 border-info/50 border-l-2 rounded-none");
+    }
+}
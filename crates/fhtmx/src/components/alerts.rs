@@ -1,13 +1,13 @@
 use super::{daisy::*, icons};
-use crate::{element::Element, html_element::*, node::*, svg::SvgElement};
+use crate::{aria::Role, element::Element, html_element::*, node::*, svg::SvgElement};
 use pastey::paste;
 
 /// Creates a new Daisy alert component.
 /// Alert informs users about important events
 pub fn mk_alert(icon: SvgElement, content: impl IntoNode) -> HtmlElement {
     dc_alert()
-        .role("alert")
-        .add(icon.class("h-6 w-6"))
+        .role(Role::Alert)
+        .add(icon.class("h-6 w-6").aria_hidden(true))
         .add(content)
 }
 
@@ -37,7 +37,7 @@ mod test {
         let res = mk_alert_error("Some error").render();
         insta::assert_snapshot!(res, @r#"
         <div class="alert alert-error" role="alert">
-          <svg class="h-6 w-6" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none" stroke="currentColor">
+          <svg class="h-6 w-6" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none" stroke="currentColor" aria-hidden="true">
             <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 14l2-2m0 0l2-2m-2 2l-2-2m2 2l2 2m7-2a9 9 0 11-18 0 9 9 0 0118 0z"></path>
           </svg>
           <span class="whitespace-pre-wrap">Some error</span>
@@ -2,7 +2,7 @@ use crate::{element::Element, html_element::*};
 use pastey::paste;
 
 macro_rules! daisy_component {
-    ($class:literal; $element:ident; $doc:literal) => {
+    (@component $class:literal; $element:ident; $doc:literal) => {
         paste! {
             #[doc = "Creates a new Daisy " $class " component: `" $element "().class(\"" $class "\")`.\n" $doc]
             pub fn [<dc_ $class>]() -> HtmlElement {
@@ -11,7 +11,7 @@ macro_rules! daisy_component {
         }
     };
 
-    ($class:literal; $element:ident; $doc:literal; $xtra_k:ident=$xtra_v:literal) => {
+    (@component $class:literal; $element:ident; $doc:literal; $xtra_k:ident=$xtra_v:literal) => {
         paste! {
             #[doc = "Creates a new Daisy " $class " component: `" $element "().class(\"" $class "\")." $xtra_k "(\"" $xtra_v "\")`.\n" $doc]
             pub fn [<dc_ $class>]() -> HtmlElement {
@@ -20,12 +20,21 @@ macro_rules! daisy_component {
         }
     };
 
-    (
-        $class:literal; $element:ident; $doc:literal $(;$xtra_k:ident=$xtra_v:literal)?,
-        $($class_rest:literal; $element_rest:ident; $doc_rest:literal $(;$xtra_k_rest:ident=$xtra_v_rest:literal)?),+ $(,)?
-    ) => {
-        daisy_component!($class; $element; $doc $(;$xtra_k=$xtra_v)?);
-        daisy_component!($($class_rest; $element_rest; $doc_rest $(;$xtra_k_rest=$xtra_v_rest)?),+);
+    (@xtra $xtra_k:ident=$xtra_v:literal) => {
+        Some((stringify!($xtra_k), $xtra_v))
+    };
+
+    (@xtra) => {
+        None
+    };
+
+    ($($class:literal; $element:ident; $doc:literal $(;$xtra_k:ident=$xtra_v:literal)?),+ $(,)?) => {
+        $(daisy_component!(@component $class; $element; $doc $(;$xtra_k=$xtra_v)?);)+
+
+        /// Daisy components as `(class, element, extra setter and value)`
+        pub(crate) const DAISY_COMPONENTS: &[(&str, &str, Option<(&str, &str)>)] = &[
+            $(($class, stringify!($element), daisy_component!(@xtra $($xtra_k=$xtra_v)?))),+
+        ];
     };
 }
 
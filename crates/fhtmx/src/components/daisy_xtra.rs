@@ -1,17 +1,42 @@
 use super::daisy::*;
-use crate::{element::Element, html_element::*, node::*, utils::random_id};
+use crate::{
+    aria::{AriaHasPopup, Role},
+    element::Element,
+    html_element::*,
+    node::*,
+    utils::random_id,
+};
+use fhtmx_derive::component;
 use std::borrow::Cow;
 
-/// Creates a new Daisy card component.
-/// Cards are used to group and display content
-pub fn mk_card(title: Option<&str>, content: impl IntoNode) -> HtmlElement {
+/// Daisy card component, cards are used to group and display content.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let res = card().title("Card title").add(p().add("Some content")).build();
+/// ```
+#[component]
+pub fn card(title: Option<&str>, children: Vec<HtmlNode>) -> HtmlElement {
     dc_card().add(
         dc_card_body()
             .add_opt(title.map(|x| dc_card_title().add(x)))
-            .add(content),
+            .add_children(children),
     )
 }
 
+/// Creates a new Daisy card component, see `card`.
+/// Cards are used to group and display content
+pub fn mk_card(title: Option<&str>, content: impl IntoNode) -> HtmlElement {
+    let card = card().add(content);
+    match title {
+        Some(x) => card.title(x),
+        None => card,
+    }
+    .build()
+}
+
 /// Creates a new Daisy dropdown component.
 /// Dropdown can open a menu or any other element when the button is clicked
 pub fn mk_dropdown<T, I, Q>(
@@ -27,7 +52,12 @@ where
     Q: Into<Cow<'static, str>>,
 {
     dc_dropdown()
-        .add(summary().class(summary_cls).add(summary_txt))
+        .add(
+            summary()
+                .class(summary_cls)
+                .aria_haspopup(AriaHasPopup::Menu)
+                .add(summary_txt),
+        )
         .add(
             dc_dropdown_content()
                 .add_class("menu")
@@ -48,7 +78,7 @@ where
         .add(
             div()
                 .tabindex(0)
-                .role("button")
+                .role(Role::Button)
                 .class("btn")
                 .add_class(btn_cls)
                 .add(btn_txt),
@@ -132,6 +162,30 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn card_component_borrows_props() {
+        let title = String::from("Card title");
+        let res = card()
+            .title(&title)
+            .add(p().add("Some content"))
+            .add(p().add("More content"));
+        let res = div().add(res).add(mk_card(None, "No title")).render();
+        insta::assert_snapshot!(res, @r#"
+        <div>
+          <div class="card">
+            <div class="card-body">
+              <h2 class="card-title">Card title</h2>
+              <p>Some content</p>
+              <p>More content</p>
+            </div>
+          </div>
+          <div class="card">
+            <div class="card-body">No title</div>
+          </div>
+        </div>
+        "#);
+    }
+
     #[test]
     fn mk_dropdown_works() {
         let res = mk_dropdown(
@@ -143,7 +197,7 @@ mod tests {
         .render();
         insta::assert_snapshot!(res, @r#"
         <details class="dropdown">
-          <summary class="btn m-1">open or close</summary>
+          <summary class="btn m-1" aria-haspopup="menu">open or close</summary>
           <ul class="dropdown-content menu bg-base-100 rounded-box z-1 w-52 p-2 shadow-sm">
             <li><a>Item 1</a></li>
             <li><a>Item 2</a></li>
@@ -1,17 +1,36 @@
-use crate::{element::Element, html_element::*};
+use crate::{element::Element, html_element::*, sanitize::SanitizePolicy};
 use pulldown_cmark::{Options, Parser};
 
+fn markdown_to_html(md: &str) -> String {
+    let md = md.trim();
+    let parser = Parser::new_ext(md, Options::all());
+    let mut html_output = String::with_capacity(md.len() * 3 / 2);
+    pulldown_cmark::html::push_html(&mut html_output, parser);
+    html_output
+}
+
 /// Renders markdown inside a `div().class("prose")`
 ///
+/// Html inside the markdown is kept as it is, use `mk_safe_markdown` for user written content.
+///
 /// Other options:
 /// - prose-sm, prose-lg, prose-xl, prose-2xl
 /// - prose-slate, prose-zinc, prose-neutral, prose-stone, prose-invert (dark mode)
 pub fn mk_markdown(md: impl AsRef<str>) -> HtmlElement {
-    let md = md.as_ref().trim();
-    let parser = Parser::new_ext(md, Options::all());
-    let mut html_output = String::with_capacity(md.len() * 3 / 2);
-    pulldown_cmark::html::push_html(&mut html_output, parser);
-    div().class("prose").add_raw(html_output)
+    div().class("prose").add_raw(markdown_to_html(md.as_ref()))
+}
+
+/// Renders untrusted markdown inside a `div().class("prose")`, cleaning the generated html with
+/// the default `SanitizePolicy`
+pub fn mk_safe_markdown(md: impl AsRef<str>) -> HtmlElement {
+    mk_markdown_with_policy(md, &SanitizePolicy::default())
+}
+
+/// Renders markdown inside a `div().class("prose")`, cleaning the generated html with `policy`
+pub fn mk_markdown_with_policy(md: impl AsRef<str>, policy: &SanitizePolicy) -> HtmlElement {
+    div()
+        .class("prose")
+        .add_raw(policy.clean_html(&markdown_to_html(md.as_ref())))
 }
 
 #[cfg(test)]
@@ -42,4 +61,24 @@ mod test {
         </div>
         "#);
     }
+
+    #[test]
+    fn render_safe_markdown_works() {
+        let md = r#"# Title <script>alert(1)</script>
+Some [link](javascript:alert(1)) and ![img](/a.png "A") <img src=x onerror="alert(1)">
+
+<div onclick="alert(1)">**html**</div>
+
+- [x] Done
+
+```html
+<b>code</b>
+```"#;
+        let res = mk_safe_markdown(md).render();
+        insta::assert_snapshot!(res, @r#"
+        <div class="prose"><h1>Title </h1><p>Some <a>link</a> and <img src="/a.png" alt="img" title="A" /> <img src="x" /></p><div>**html**</div><ul><li><input disabled="" type="checkbox" checked="" />
+        Done</li></ul><pre><code>&lt;b&gt;code&lt;/b&gt;
+        </code></pre></div>
+        "#);
+    }
 }
@@ -29,6 +29,7 @@ pub fn theme_toogle_with_size(size: u8) -> HtmlElement {
         .add(
             input()
                 .typ("checkbox")
+                .aria_label("Toggle dark mode")
                 .set_attr(":checked", "lightMode")
                 .set_attr(
                     "@change",
@@ -1,22 +1,47 @@
 use crate::{
     attribute::{AttributeValue, IntoAttributeValue},
+    class_merge::merge_class_into,
     node::{HtmlNode, IntoNode, raw_node},
     prelude::HtmlElement,
+    safe_url::IntoUrlValue,
+    select::{self, ElementRef},
+    style::{IntoStyleValue, is_css_property_name, merge_style, remove_declaration},
+    walk::{self, Walk},
 };
 use indexmap::{IndexMap, IndexSet};
 use std::borrow::Cow;
 
 pub trait Element: Sized {
-    fn tag(&self) -> &'static str;
+    fn tag(&self) -> &str;
     fn attrs(&self) -> &IndexMap<Cow<'static, str>, AttributeValue>;
     fn attrs_mut(&mut self) -> &mut IndexMap<Cow<'static, str>, AttributeValue>;
     fn classes(&self) -> &IndexSet<Cow<'static, str>>;
     fn classes_mut(&mut self) -> &mut IndexSet<Cow<'static, str>>;
+
+    /// Style properties, rendered into the `style` attribute after its own declarations. `None`
+    /// when the element doesn't store them, then `style_prop` merges them into the attribute.
+    #[inline]
+    fn styles(&self) -> Option<&IndexMap<Cow<'static, str>, String>> {
+        None
+    }
+
+    #[inline]
+    fn styles_mut(&mut self) -> Option<&mut IndexMap<Cow<'static, str>, String>> {
+        None
+    }
+
     fn children(&self) -> &[HtmlNode];
     fn children_mut(&mut self) -> &mut Vec<HtmlNode>;
     fn is_void_tag(&self) -> bool;
     fn is_inline_tag(&self) -> bool;
 
+    /// Whether the content is raw text (`script` and `style`): text children are rendered
+    /// unescaped, with the end tag escaped
+    #[inline]
+    fn is_raw_text_tag(&self) -> bool {
+        false
+    }
+
     #[inline]
     fn len(&self) -> usize {
         self.children().len()
@@ -40,6 +65,7 @@ pub trait Element: Sized {
         let has_block = self.children().iter().any(|o| match o {
             HtmlNode::Element(x) => !x.is_inline_tag(),
             HtmlNode::SvgElement(x) => !x.is_inline_tag(),
+            HtmlNode::Prerendered(x) => x.is_block(),
             _ => false,
         });
         !has_block
@@ -125,6 +151,91 @@ pub trait Element: Sized {
         self
     }
 
+    /// Sets an attribute holding an url, unsafe urls (eg: `javascript:`) are replaced by
+    /// `about:invalid`. See `is_safe_url` and `TrustedUrl`.
+    fn set_url_attr_mut<K, V>(&mut self, attr: K, value: V)
+    where
+        K: Into<Cow<'static, str>>,
+        V: IntoUrlValue,
+    {
+        let attr = attr.into();
+        if let Some(v) = value.into_url_attr(&attr) {
+            self.attrs_mut().insert(attr, v);
+        }
+    }
+
+    /// Sets an attribute holding an url, unsafe urls (eg: `javascript:`) are replaced by
+    /// `about:invalid`. See `is_safe_url` and `TrustedUrl`.
+    fn set_url_attr<K, V>(mut self, attr: K, value: V) -> Self
+    where
+        K: Into<Cow<'static, str>>,
+        V: IntoUrlValue,
+    {
+        self.set_url_attr_mut(attr, value);
+        self
+    }
+
+    /// Sets a `data-*` attribute, `key` goes without the `data-` prefix.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is not a valid `data-*` name (empty, or with characters other than lowercase
+    /// letters, digits, `-`, `_` and `.`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let res = tr().data("row-id", 12).data("selected", true).render();
+    /// assert_eq!(res, r#"<tr data-row-id="12" data-selected></tr>"#);
+    /// ```
+    #[track_caller]
+    fn data_mut(&mut self, key: &str, value: impl IntoAttributeValue) {
+        assert!(
+            is_valid_data_key(key),
+            "invalid data attribute name: `{key}`"
+        );
+        self.set_attr_mut(format!("data-{key}"), value);
+    }
+
+    /// Sets a `data-*` attribute, see `data_mut`
+    #[track_caller]
+    fn data(mut self, key: &str, value: impl IntoAttributeValue) -> Self {
+        self.data_mut(key, value);
+        self
+    }
+
+    /// Sets a `data-*` attribute to `value` serialized as json.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is not a valid `data-*` name (see `data_mut`) or `value` can't be serialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let res = div().data_json("config", &["a", "b"]).render();
+    /// assert_eq!(res, r#"<div data-config="[&quot;a&quot;,&quot;b&quot;]"></div>"#);
+    /// ```
+    #[cfg(feature = "serde")]
+    #[track_caller]
+    fn data_json_mut(&mut self, key: &str, value: &impl serde::Serialize) {
+        let json = match serde_json::to_string(value) {
+            Ok(x) => x,
+            Err(e) => panic!("failed to serialize data attribute `{key}`: {e}"),
+        };
+        self.data_mut(key, json);
+    }
+
+    /// Sets a `data-*` attribute to `value` serialized as json, see `data_json_mut`
+    #[cfg(feature = "serde")]
+    #[track_caller]
+    fn data_json(mut self, key: &str, value: &impl serde::Serialize) -> Self {
+        self.data_json_mut(key, value);
+        self
+    }
+
     fn set_empty_attr_mut(&mut self, attr: impl Into<Cow<'static, str>>) {
         self.attrs_mut().insert(attr.into(), AttributeValue::Empty);
     }
@@ -184,6 +295,34 @@ pub trait Element: Sized {
         self
     }
 
+    /// Adds classes replacing the earlier ones they override, following Tailwind utility groups
+    /// and variants (eg: `p-2` replaces `p-4` and `px-4`, `hover:bg-primary` replaces
+    /// `hover:bg-base-100`). See `tw_merge`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let res = div().class("p-4 bg-base-100").merge_class("p-2").render();
+    /// assert_eq!(res, r#"<div class="bg-base-100 p-2"></div>"#);
+    /// ```
+    fn merge_class_mut(&mut self, class: impl Into<Cow<'static, str>>) {
+        let class = class.into();
+        if class.contains(char::is_whitespace) {
+            class.split_whitespace().for_each(|o| {
+                merge_class_into(self.classes_mut(), o.to_string().into());
+            });
+        } else if !class.is_empty() {
+            merge_class_into(self.classes_mut(), class);
+        }
+    }
+
+    /// Adds classes replacing the earlier ones they override, see `merge_class_mut`
+    fn merge_class(mut self, class: impl Into<Cow<'static, str>>) -> Self {
+        self.merge_class_mut(class);
+        self
+    }
+
     fn add_opt_class_mut(&mut self, class: Option<impl Into<Cow<'static, str>>>) {
         if let Some(class) = class {
             self.add_class_mut(class);
@@ -218,6 +357,86 @@ pub trait Element: Sized {
         self
     }
 
+    /// Sets a style property, replacing its previous value but keeping the other properties and
+    /// the `style` attribute. Values that would end their declaration (eg: `red; top: 0`) are
+    /// escaped when rendering, so browsers drop them.
+    ///
+    /// # Panics
+    ///
+    /// If `name` is not a css property name (eg: `color`, `-webkit-line-clamp` or `--gap`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let res = div()
+    ///     .style("color: red")
+    ///     .style_prop("width", CssLength::Px(10.0))
+    ///     .style_prop("display", CssDisplay::Flex)
+    ///     .style_prop("display", "none")
+    ///     .render();
+    /// assert_eq!(res, r#"<div style="color: red; width: 10px; display: none"></div>"#);
+    /// ```
+    #[track_caller]
+    fn style_prop_mut(&mut self, name: impl Into<Cow<'static, str>>, value: impl IntoStyleValue) {
+        let name = name.into();
+        assert!(
+            is_css_property_name(&name),
+            "invalid css property name: `{name}`"
+        );
+        let value = value.into_style_value();
+        match self.styles_mut() {
+            Some(styles) => {
+                styles.insert(name, value);
+            }
+            None => {
+                let style = match self.attrs().get("style") {
+                    Some(AttributeValue::Value(x) | AttributeValue::Raw(x)) => {
+                        remove_declaration(x, &name)
+                    }
+                    _ => String::new(),
+                };
+                let style = AttributeValue::Value(style);
+                let style = merge_style(Some(&style), &IndexMap::from([(name, value)]));
+                self.attrs_mut()
+                    .insert("style".into(), AttributeValue::Value(style));
+            }
+        }
+    }
+
+    /// Sets a style property, see `style_prop_mut`
+    #[track_caller]
+    fn style_prop(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl IntoStyleValue,
+    ) -> Self {
+        self.style_prop_mut(name, value);
+        self
+    }
+
+    /// Removes a style property set with `style_prop`
+    fn remove_style_prop_mut(&mut self, name: &str) {
+        match self.styles_mut() {
+            Some(styles) => {
+                styles.shift_remove(name);
+            }
+            None => {
+                if let Some(AttributeValue::Value(style) | AttributeValue::Raw(style)) =
+                    self.attrs_mut().get_mut("style")
+                {
+                    *style = remove_declaration(style, name);
+                }
+            }
+        }
+    }
+
+    /// Removes a style property set with `style_prop`
+    fn remove_style_prop(mut self, name: &str) -> Self {
+        self.remove_style_prop_mut(name);
+        self
+    }
+
     /// Adds a raw html child
     fn add_raw_mut(&mut self, raw: impl ToString) {
         self.children_mut().push(raw_node(raw));
@@ -414,10 +633,84 @@ pub trait Element: Sized {
         self.update_html_element_mut(index, f);
         self
     }
+
+    /// Gets all the descendants matching a css selector, in document order. The element itself
+    /// is never returned, but it takes part in the combinators (eg: `.card > p`).
+    ///
+    /// See `Selector` for the supported syntax.
+    ///
+    /// # Panics
+    ///
+    /// If the selector is invalid, use `Selector::parse` to validate selectors from user input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let card = mk_card(Some("Title"), p().add("Content"));
+    /// assert_eq!(card.select("div.card-body > h2").len(), 1);
+    /// ```
+    fn select(&self, selector: &str) -> Vec<&HtmlNode> {
+        select::select(Some(ElementRef::new(self)), self.children(), selector)
+    }
+
+    /// Gets the first descendant matching a css selector.
+    ///
+    /// # Panics
+    ///
+    /// If the selector is invalid.
+    fn select_first(&self, selector: &str) -> Option<&HtmlNode> {
+        select::select_first(Some(ElementRef::new(self)), self.children(), selector)
+    }
+
+    /// Calls `f` on every descendant matching a css selector. Nodes are visited in reverse
+    /// document order, so `f` can freely modify or replace them.
+    ///
+    /// # Panics
+    ///
+    /// If the selector is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let mut list = ul().add(li().add("a")).add(li().add("b"));
+    /// list.select_mut("li", |o| {
+    ///     if let Some(o) = o.as_element_mut() {
+    ///         o.add_class_mut("item");
+    ///     }
+    /// });
+    /// ```
+    fn select_mut(&mut self, selector: &str, f: impl FnMut(&mut HtmlNode)) {
+        let paths =
+            select::select_paths_for(Some(ElementRef::new(self)), self.children(), selector);
+        select::update_paths(self.children_mut(), &paths, f);
+    }
+
+    /// Gets the descendant with the given `id`
+    fn find_by_id(&self, id: &str) -> Option<&HtmlNode> {
+        select::find_by_id(Some(ElementRef::new(self)), self.children(), id)
+    }
+
+    /// Gets the descendant with the given `id`
+    fn find_by_id_mut(&mut self, id: &str) -> Option<&mut HtmlNode> {
+        select::find_by_id_mut(self.children_mut(), id)
+    }
+
+    /// Visits all the descendants in document order, see `HtmlNode::walk`
+    fn walk<'a>(&'a self, f: impl FnMut(&'a HtmlNode) -> Walk) {
+        walk::walk_nodes(self.children(), f);
+    }
+
+    /// Visits all the descendants in document order allowing to modify them, see
+    /// `HtmlNode::walk_mut`
+    fn walk_mut(&mut self, f: impl FnMut(&mut HtmlNode) -> Walk) {
+        walk::walk_nodes_mut(self.children_mut(), f);
+    }
 }
 
 macro_rules! set_attr {
-    ($attr:ident = $name:expr; eg = $eg:expr) => {
+    (@setter $attr:ident = $name:expr; eg = $eg:expr) => {
         paste! {
             #[doc = "Sets the `" $name "` attribute.\nExample: `" $eg "`"]
             pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
@@ -426,7 +719,7 @@ macro_rules! set_attr {
         }
     };
 
-    ($attr:ident = $name:expr) => {
+    (@setter $attr:ident = $name:expr) => {
         paste! {
             #[doc = "Sets the `" $name "` attribute."]
             pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
@@ -435,7 +728,7 @@ macro_rules! set_attr {
         }
     };
 
-    ($attr:ident) => {
+    (@setter $attr:ident) => {
         paste! {
             #[doc = "Sets the `" $attr "` attribute."]
             pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
@@ -444,17 +737,20 @@ macro_rules! set_attr {
         }
     };
 
-    ($attr:ident$(=$name:expr)?$(;eg=$eg:expr)?, $($rest:ident$(=$name_rest:expr)?$(;eg=$eg_rest:expr)?),+) => {
-        set_attr!($attr$(=$name)?$(;eg=$eg)?);
-        set_attr!($($rest$(=$name_rest)?$(;eg=$eg_rest)?),+);
+    ($($attr:ident$(=$name:expr)?$(;eg=$eg:expr)?),+ $(,)?) => {
+        $(set_attr!(@setter $attr$(=$name)?$(;eg=$eg)?);)+
+
+        /// Setters defined with `set_attr!` as `(method, attribute)` pairs
+        pub(crate) const ATTR_SETTERS: &[(&str, &str)] = &[
+            $((stringify!($attr), $crate::element::attr_name!($attr$(=$name)?))),+
+        ];
     };
 }
 
 pub(crate) use set_attr;
 
-// TODO: simplify
 macro_rules! set_empty_attr {
-    ($attr:ident = $name:expr) => {
+    (@setter $attr:ident = $name:expr) => {
         paste! {
             #[doc = "Sets the `" $name "` empty attribute."]
             pub fn $attr(self) -> Self {
@@ -463,7 +759,7 @@ macro_rules! set_empty_attr {
         }
     };
 
-    ($attr:ident) => {
+    (@setter $attr:ident) => {
         paste! {
             #[doc = "Sets the `" $attr "` empty attribute."]
             pub fn $attr(self) -> Self {
@@ -472,14 +768,79 @@ macro_rules! set_empty_attr {
         }
     };
 
-    ($attr:ident$(=$name:expr)?, $($rest:ident$(=$name_rest:expr)?),+) => {
-        set_empty_attr!($attr$(=$name)?);
-        set_empty_attr!($($rest$(=$name_rest)?),+);
+    ($($attr:ident$(=$name:expr)?),+ $(,)?) => {
+        $(set_empty_attr!(@setter $attr$(=$name)?);)+
+
+        /// Setters defined with `set_empty_attr!` as `(method, attribute)` pairs
+        pub(crate) const EMPTY_ATTR_SETTERS: &[(&str, &str)] = &[
+            $((stringify!($attr), $crate::element::attr_name!($attr$(=$name)?))),+
+        ];
     };
 }
 
 pub(crate) use set_empty_attr;
 
+macro_rules! set_url_attr {
+    (@setter $attr:ident = $name:expr; eg = $eg:expr) => {
+        paste! {
+            #[doc = "Sets the `" $name "` url attribute, unsafe urls are replaced by `about:invalid`.\nExample: `" $eg "`"]
+            pub fn $attr(self, value: impl IntoUrlValue) -> Self {
+                self.set_url_attr($name, value)
+            }
+        }
+    };
+
+    (@setter $attr:ident = $name:expr) => {
+        paste! {
+            #[doc = "Sets the `" $name "` url attribute, unsafe urls are replaced by `about:invalid`."]
+            pub fn $attr(self, value: impl IntoUrlValue) -> Self {
+                self.set_url_attr($name, value)
+            }
+        }
+    };
+
+    (@setter $attr:ident) => {
+        paste! {
+            #[doc = "Sets the `" $attr "` url attribute, unsafe urls are replaced by `about:invalid`."]
+            pub fn $attr(self, value: impl IntoUrlValue) -> Self {
+                self.set_url_attr(stringify!([< $attr:lower >]), value)
+            }
+        }
+    };
+
+    ($setters:ident: $($attr:ident$(=$name:expr)?$(;eg=$eg:expr)?),+ $(,)?) => {
+        $(set_url_attr!(@setter $attr$(=$name)?$(;eg=$eg)?);)+
+
+        /// Setters defined with `set_url_attr!` as `(method, attribute)` pairs
+        pub(crate) const $setters: &[(&str, &str)] = &[
+            $((stringify!($attr), $crate::element::attr_name!($attr$(=$name)?))),+
+        ];
+    };
+}
+
+pub(crate) use set_url_attr;
+
+/// Checks the name of a `data-*` attribute (without the prefix)
+pub(crate) fn is_valid_data_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.bytes().all(|o| {
+            o.is_ascii_lowercase() || o.is_ascii_digit() || matches!(o, b'-' | b'_' | b'.')
+        })
+}
+
+/// Attribute name set by a setter macro
+macro_rules! attr_name {
+    ($attr:ident = $name:expr) => {
+        $name
+    };
+
+    ($attr:ident) => {
+        paste! { stringify!([< $attr:lower >]) }
+    };
+}
+
+pub(crate) use attr_name;
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -534,4 +895,99 @@ mod test {
         </div>
         ");
     }
+
+    #[test]
+    fn data_attrs_work() {
+        #[derive(serde::Serialize)]
+        struct Config<'a> {
+            id: u32,
+            name: &'a str,
+        }
+
+        let res = div()
+            .data("user-id", 12)
+            .data("active", true)
+            .data("hidden", false)
+            .data("v1.2_x", "a")
+            .data_json(
+                "config",
+                &Config {
+                    id: 1,
+                    name: "O'Brien",
+                },
+            )
+            .render();
+        insta::assert_snapshot!(res, @r#"<div data-user-id="12" data-active data-v1.2_x="a" data-config="{&quot;id&quot;:1,&quot;name&quot;:&quot;O&#x27;Brien&quot;}"></div>"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid data attribute name: `userId`")]
+    fn data_rejects_invalid_keys() {
+        div().data("userId", 1);
+    }
+
+    #[test]
+    fn custom_elements_work() {
+        let tag = String::from("x-chart");
+        let res = custom_element("my-card")
+            .class("card")
+            .add(
+                template()
+                    .shadowrootmode(ShadowRootMode::Open)
+                    .shadowrootdelegatesfocus()
+                    .add(slot().set_attr("name", "title")),
+            )
+            .add(HtmlElement::new(tag).set_attr("data-src", "/stats"))
+            .add(custom_element("x-icon"))
+            .render();
+        insta::assert_snapshot!(res, @r#"
+        <my-card class="card">
+          <template shadowrootmode="open" shadowrootdelegatesfocus>
+            <slot name="title"></slot>
+          </template>
+          <x-chart data-src="/stats"></x-chart>
+          <x-icon></x-icon>
+        </my-card>
+        "#);
+    }
+
+    #[test]
+    fn invalid_tag_names() {
+        assert!(HtmlElement::try_new("my-widget").is_ok());
+        assert!(HtmlElement::try_new("h1").is_ok());
+        assert_eq!(
+            HtmlElement::try_new("di v").unwrap_err().to_string(),
+            "invalid tag name `di v`"
+        );
+        assert!(HtmlElement::try_new("").is_err());
+        assert!(HtmlElement::try_new("1div").is_err());
+        assert!(HtmlElement::try_new("a>b").is_err());
+        assert!(is_valid_custom_element_name("x-chart"));
+        assert!(!is_valid_custom_element_name("chart"));
+        assert!(!is_valid_custom_element_name("X-Chart"));
+        assert!(!is_valid_custom_element_name("font-face"));
+    }
+
+    #[test]
+    fn url_attrs_match_the_setters() {
+        use crate::{safe_url::URL_ATTRS, svg::*};
+        use std::collections::BTreeSet;
+
+        let setters = [
+            HtmlElement::URL_ATTR_SETTERS,
+            HtmlElement::HTMX_URL_ATTR_SETTERS,
+            SvgElement::URL_ATTR_SETTERS,
+        ];
+        let attrs = setters
+            .iter()
+            .flat_map(|o| o.iter().map(|(_, attr)| *attr))
+            .collect::<BTreeSet<_>>();
+        assert_eq!(URL_ATTRS.iter().copied().collect::<BTreeSet<_>>(), attrs);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid custom element name `widget`")]
+    fn custom_element_rejects_invalid_names() {
+        custom_element("widget");
+    }
 }
@@ -0,0 +1,522 @@
+use crate::{
+    aria::{EnumSetter, Role},
+    attribute::AttributeValue,
+    components::DAISY_COMPONENTS,
+    element::{Element, is_valid_data_key},
+    html_element::{HTML_ELEMENTS, HtmlElement, is_valid_custom_element_name},
+    node::HtmlNode,
+    parse::ParseError,
+    safe_url::is_safe_url_attr,
+    svg::{SVG_ELEMENTS, SvgElement},
+};
+
+/// Max width of the generated lines
+const MAX_WIDTH: usize = 100;
+
+/// Max width of a chain of calls kept in a single line
+const CHAIN_WIDTH: usize = 60;
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// Converts a html snippet into fhtmx builder code.
+///
+/// Uses the `dc_*` constructors for daisy components, `svg_*` for svg elements and the typed
+/// setters (eg: `.href()`, `.hx_get()`) when they exist. A snippet with several top level nodes
+/// is returned as a `children![...]` list.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let code = html_to_fhtmx(r#"<button class="btn" hx-get="/items">Load</button>"#).unwrap();
+/// assert_eq!(code, r#"dc_btn().hx_get("/items").add("Load")"#);
+/// ```
+pub fn html_to_fhtmx(html: &str) -> Result<String, ParseError> {
+    let nodes = match HtmlNode::parse(html)? {
+        HtmlNode::Fragment(nodes) => nodes,
+        node => vec![node],
+    };
+    let mut nodes = nodes.iter().filter_map(node_code).collect::<Vec<_>>();
+    let res = match nodes.len() {
+        0 => "children![]".to_string(),
+        1 => nodes.remove(0).layout(0),
+        _ => {
+            let mut res = "children![\n".to_string();
+            for node in nodes {
+                res.push_str(&pad(4));
+                res.push_str(&node.layout(4));
+                res.push_str(",\n");
+            }
+            res.push(']');
+            res
+        }
+    };
+    Ok(res)
+}
+
+/// Builder code of a node: a constructor followed by method calls
+struct Code {
+    ctor: String,
+    calls: Vec<Call>,
+}
+
+enum Call {
+    /// A call with literal arguments, eg: `.class("card")`
+    Simple(String),
+    /// A call that adds a child, eg: `.add(p())`
+    Add(Code),
+}
+
+impl Code {
+    fn new(ctor: impl ToString) -> Self {
+        Self {
+            ctor: ctor.to_string(),
+            calls: Vec::new(),
+        }
+    }
+
+    fn call(&mut self, method: &str, args: &[&str]) {
+        self.calls
+            .push(Call::Simple(format!(".{method}({})", args.join(", "))));
+    }
+
+    /// Single line version of the code
+    fn flat(&self) -> String {
+        let mut res = self.ctor.clone();
+        for call in &self.calls {
+            match call {
+                Call::Simple(s) => res.push_str(s),
+                Call::Add(child) => {
+                    res.push_str(".add(");
+                    res.push_str(&child.flat());
+                    res.push(')');
+                }
+            }
+        }
+        res
+    }
+
+    /// Formats the code (like rustfmt would) starting at column `col`
+    fn layout(&self, col: usize) -> String {
+        let flat = self.flat();
+        if self.calls.is_empty() || (flat.len() <= CHAIN_WIDTH && col + flat.len() <= MAX_WIDTH) {
+            return flat;
+        }
+        let mut res = self.ctor.clone();
+        for call in &self.calls {
+            res.push('\n');
+            res.push_str(&pad(col + 4));
+            match call {
+                Call::Simple(s) => res.push_str(s),
+                Call::Add(child) => {
+                    let child_flat = child.flat();
+                    let fits = child_flat.len() <= CHAIN_WIDTH
+                        && col + 4 + ".add()".len() + child_flat.len() <= MAX_WIDTH;
+                    if child.calls.is_empty() || fits {
+                        res.push_str(&format!(".add({child_flat})"));
+                    } else {
+                        res.push_str(".add(\n");
+                        res.push_str(&pad(col + 8));
+                        res.push_str(&child.layout(col + 8));
+                        res.push_str(",\n");
+                        res.push_str(&pad(col + 4));
+                        res.push(')');
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+fn node_code(node: &HtmlNode) -> Option<Code> {
+    let code = match node {
+        HtmlNode::Doctype => Code::new("HtmlNode::Doctype"),
+        HtmlNode::Raw(s) => Code::new(format!("raw_node({})", str_literal(s))),
+        HtmlNode::Prerendered(x) => Code::new(format!("raw_node({})", str_literal(x.as_str()))),
+        HtmlNode::Text(s) => Code::new(str_literal(&normalize_text(s)?)),
+        HtmlNode::Element(el) => element_code(el),
+        HtmlNode::SvgElement(el) => svg_code(el),
+        HtmlNode::Fragment(nodes) => {
+            let nodes = nodes.iter().filter_map(node_code).map(|o| o.flat());
+            Code::new(format!(
+                "fragment(children![{}])",
+                nodes.collect::<Vec<_>>().join(", ")
+            ))
+        }
+    };
+    Some(code)
+}
+
+fn element_code(el: &HtmlElement) -> Code {
+    let mut classes = el.classes.iter().map(|o| o.as_ref()).collect::<Vec<_>>();
+    let mut attrs = el.attrs.iter().collect::<Vec<_>>();
+    let daisy = classes.iter().enumerate().find_map(|(i, class)| {
+        DAISY_COMPONENTS
+            .iter()
+            .filter(|(k, element, _)| k == class && *element == el.tag)
+            .find_map(|(_, _, xtra)| match xtra {
+                None => Some((i, None)),
+                Some((setter, value)) => {
+                    let attr = find_attr_name(HtmlElement::ATTR_SETTERS, setter)?;
+                    let j = attrs.iter().position(|(k, v)| {
+                        k.as_ref() == attr && matches!(v, AttributeValue::Value(v) if v == value)
+                    })?;
+                    Some((i, Some(j)))
+                }
+            })
+    });
+    let mut code = match daisy {
+        Some((i, attr)) => {
+            if let Some(j) = attr {
+                attrs.remove(j);
+            }
+            let class = classes.remove(i);
+            Code::new(format!("dc_{}()", class.replace('-', "_")))
+        }
+        None if el.tag == "main" => Code::new("main_tag()"),
+        None if HTML_ELEMENTS.contains(&el.tag()) => Code::new(format!("{}()", el.tag)),
+        None if is_valid_custom_element_name(&el.tag) => {
+            Code::new(format!("custom_element({})", str_literal(&el.tag)))
+        }
+        None => Code::new(format!("HtmlElement::new({})", str_literal(&el.tag))),
+    };
+    if !classes.is_empty() {
+        let method = if daisy.is_some() {
+            "add_class"
+        } else {
+            "class"
+        };
+        code.call(method, &[&str_literal(&classes.join(" "))]);
+    }
+    let setters = [HtmlElement::ATTR_SETTERS, HtmlElement::HTMX_ATTR_SETTERS];
+    let url_setters = [
+        HtmlElement::URL_ATTR_SETTERS,
+        HtmlElement::HTMX_URL_ATTR_SETTERS,
+    ];
+    for (k, v) in attrs {
+        let setters = Setters {
+            values: &setters,
+            urls: &url_setters,
+            empty: HtmlElement::EMPTY_ATTR_SETTERS,
+            aria_bools: HtmlElement::ARIA_BOOL_SETTERS,
+            aria_values: &[
+                HtmlElement::ARIA_ID_REFS_SETTERS,
+                HtmlElement::ARIA_VALUE_SETTERS,
+            ],
+            aria_enums: HtmlElement::ARIA_ENUM_SETTERS,
+        };
+        attr_code(&mut code, k, v, &setters);
+    }
+    add_children_code(&mut code, el.tag(), &el.children);
+    code
+}
+
+fn svg_code(el: &SvgElement) -> Code {
+    let mut code = match el.tag() {
+        "svg" => Code::new("svg()"),
+        tag if SVG_ELEMENTS.contains(&tag) => Code::new(format!("svg_{}()", to_snake_case(tag))),
+        tag => Code::new(format!("SvgElement::new({})", str_literal(tag))),
+    };
+    if !el.classes.is_empty() {
+        let classes = el.classes.iter().map(|o| o.as_ref()).collect::<Vec<_>>();
+        code.call("class", &[&str_literal(&classes.join(" "))]);
+    }
+    for (k, v) in &el.attrs {
+        let is_svg_namespace = matches!(v, AttributeValue::Value(v) if v == SVG_NAMESPACE);
+        if el.tag == "svg" && k == "xmlns" && is_svg_namespace {
+            // Already set by `svg()`
+            continue;
+        }
+        let setters = Setters {
+            values: &[SvgElement::ATTR_SETTERS],
+            urls: &[SvgElement::URL_ATTR_SETTERS],
+            empty: &[],
+            aria_bools: SvgElement::ARIA_BOOL_SETTERS,
+            aria_values: &[
+                SvgElement::ARIA_ID_REFS_SETTERS,
+                SvgElement::ARIA_VALUE_SETTERS,
+            ],
+            aria_enums: SvgElement::ARIA_ENUM_SETTERS,
+        };
+        attr_code(&mut code, k, v, &setters);
+    }
+    add_children_code(&mut code, el.tag(), &el.children);
+    code
+}
+
+/// `(method, attribute)` pairs of the setters of an element
+struct Setters<'a> {
+    values: &'a [&'a [(&'a str, &'a str)]],
+    urls: &'a [&'a [(&'a str, &'a str)]],
+    empty: &'a [(&'a str, &'a str)],
+    aria_bools: &'a [(&'a str, &'a str)],
+    aria_values: &'a [&'a [(&'a str, &'a str)]],
+    aria_enums: &'a [EnumSetter],
+}
+
+fn attr_code(code: &mut Code, attr: &str, value: &AttributeValue, setters: &Setters) {
+    if let Some((setter, args)) = typed_attr_code(attr, value, setters) {
+        code.call(setter, &[&args]);
+        return;
+    }
+    match value {
+        AttributeValue::Empty => match find_setter(setters.empty, attr) {
+            Some(setter) => code.call(setter, &[]),
+            None => code.call("set_empty_attr", &[&str_literal(attr)]),
+        },
+        AttributeValue::Value(v) | AttributeValue::Raw(v) => {
+            let value = str_literal(v);
+            let setter = setters.values.iter().find_map(|o| find_setter(o, attr));
+            // Url setters would replace unsafe urls, so those are kept with `set_attr`
+            let url_setter = setters
+                .urls
+                .iter()
+                .find_map(|o| find_setter(o, attr))
+                .filter(|_| is_safe_url_attr(attr, v));
+            match setter.or(url_setter) {
+                Some(setter) => code.call(setter, &[&value]),
+                None => code.call("set_attr", &[&str_literal(attr), &value]),
+            }
+        }
+    }
+}
+
+/// Setter and argument for the `data-*`, `role` and `aria-*` attributes, when the value fits
+fn typed_attr_code<'a>(
+    attr: &str,
+    value: &AttributeValue,
+    setters: &Setters<'a>,
+) -> Option<(&'a str, String)> {
+    if let Some(key) = attr.strip_prefix("data-")
+        && is_valid_data_key(key)
+    {
+        let value = match value {
+            AttributeValue::Empty => "true".to_string(),
+            AttributeValue::Value(v) | AttributeValue::Raw(v) => str_literal(v),
+        };
+        return Some(("data", format!("{}, {value}", str_literal(key))));
+    }
+    let (AttributeValue::Value(v) | AttributeValue::Raw(v)) = value else {
+        return None;
+    };
+    if attr == "role" {
+        let variant = find_variant(Role::VALUES, v)?;
+        return Some(("role", format!("Role::{variant}")));
+    }
+    if let Some(setter) = find_setter(setters.aria_bools, attr) {
+        return matches!(v.as_str(), "true" | "false").then(|| (setter, v.clone()));
+    }
+    if let Some(setter) = setters
+        .aria_values
+        .iter()
+        .find_map(|o| find_setter(o, attr))
+    {
+        return Some((setter, str_literal(v)));
+    }
+    let (setter, _, ty, values) = setters
+        .aria_enums
+        .iter()
+        .find(|(_, name, _, _)| *name == attr)?;
+    let variant = find_variant(values, v)?;
+    Some((setter, format!("{ty}::{variant}")))
+}
+
+fn find_variant<'a>(values: &[(&'a str, &str)], value: &str) -> Option<&'a str> {
+    values
+        .iter()
+        .find(|(_, v)| *v == value)
+        .map(|(variant, _)| *variant)
+}
+
+fn add_children_code(code: &mut Code, tag: &str, children: &[HtmlNode]) {
+    for child in children {
+        match child {
+            HtmlNode::Raw(s) => code.call("add_raw", &[&str_literal(s)]),
+            HtmlNode::Prerendered(x) => code.call("add_raw", &[&str_literal(x.as_str())]),
+            HtmlNode::Text(s) if matches!(tag, "pre" | "textarea") => {
+                code.calls.push(Call::Add(Code::new(str_literal(s))))
+            }
+            child => {
+                if let Some(child) = node_code(child) {
+                    code.calls.push(Call::Add(child));
+                }
+            }
+        }
+    }
+}
+
+fn find_setter<'a>(setters: &[(&'a str, &str)], attr: &str) -> Option<&'a str> {
+    setters
+        .iter()
+        .find(|(_, name)| *name == attr)
+        .map(|(method, _)| *method)
+}
+
+fn find_attr_name<'a>(setters: &[(&str, &'a str)], method: &str) -> Option<&'a str> {
+    setters
+        .iter()
+        .find(|(name, _)| *name == method)
+        .map(|(_, attr)| *attr)
+}
+
+/// Collapses the whitespace of text that was split in several lines in the source
+fn normalize_text(s: &str) -> Option<String> {
+    let res = if s.contains('\n') {
+        s.split_whitespace().collect::<Vec<_>>().join(" ")
+    } else {
+        s.to_string()
+    };
+    (!res.is_empty()).then_some(res)
+}
+
+/// Rust string literal for `s`, using a raw string when it contains quotes
+fn str_literal(s: &str) -> String {
+    let has_escapes = s.contains(['"', '\\']);
+    let printable = !s.chars().any(|c| c.is_control() && c != '\n');
+    if has_escapes && printable && !s.contains('\n') {
+        let mut hashes = "#".to_string();
+        while s.contains(&format!("\"{hashes}")) {
+            hashes.push('#');
+        }
+        format!("r{hashes}\"{s}\"{hashes}")
+    } else {
+        format!("{s:?}")
+    }
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 4);
+    for c in s.chars() {
+        if c.is_ascii_uppercase() {
+            res.push('_');
+            res.push(c.to_ascii_lowercase());
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+#[inline]
+fn pad(n: usize) -> String {
+    " ".repeat(n)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn html_to_fhtmx_works() {
+        let res = html_to_fhtmx(
+            r#"<div class="card bg-base-100 w-96 shadow-sm">
+  <div class="card-body">
+    <h2 class="card-title">Card Title</h2>
+    <p>A card component has a figure, a body part, and inside body there are title and
+      actions parts</p>
+    <div class="card-actions justify-end">
+      <button class="btn btn-primary" hx-post="/buy" hx-vals='{"id": 1}'>Buy Now</button>
+    </div>
+  </div>
+</div>"#,
+        )
+        .unwrap();
+        insta::assert_snapshot!(res, @r##"
+        dc_card()
+            .add_class("bg-base-100 w-96 shadow-sm")
+            .add(
+                dc_card_body()
+                    .add(dc_card_title().add("Card Title"))
+                    .add(
+                        p()
+                            .add("A card component has a figure, a body part, and inside body there are title and actions parts"),
+                    )
+                    .add(
+                        dc_card_actions()
+                            .add_class("justify-end")
+                            .add(
+                                dc_btn()
+                                    .add_class("btn-primary")
+                                    .hx_post("/buy")
+                                    .hx_vals(r#"{"id": 1}"#)
+                                    .add("Buy Now"),
+                            ),
+                    ),
+            )
+        "##);
+    }
+
+    #[test]
+    fn html_to_fhtmx_setters() {
+        let res = html_to_fhtmx(
+            r#"<input type="checkbox" class="toggle" checked data-x="1" /><a href="/home" target="_blank">Home</a><main></main><a href="javascript:void(0)">No</a>"#,
+        )
+        .unwrap();
+        insta::assert_snapshot!(res, @r#"
+        children![
+            dc_toggle().checked().data("x", "1"),
+            a().href("/home").target("_blank").add("Home"),
+            main_tag(),
+            a().set_attr("href", "javascript:void(0)").add("No"),
+        ]
+        "#);
+    }
+
+    #[test]
+    fn html_to_fhtmx_data_and_aria() {
+        let res = html_to_fhtmx(
+            r#"<div role="dialog" aria-modal="true" aria-labelledby="title" data-open data-a:b="1"><button aria-expanded="false" aria-haspopup="menu" aria-pressed="mixed" aria-live="loud" aria-hidden="maybe">Menu</button><span role="custom" aria-current="page" aria-label="Home"></span></div>"#,
+        )
+        .unwrap();
+        insta::assert_snapshot!(res, @r#"
+        div()
+            .role(Role::Dialog)
+            .aria_modal(true)
+            .aria_labelledby("title")
+            .data("open", true)
+            .set_attr("data-a:b", "1")
+            .add(
+                button()
+                    .aria_expanded(false)
+                    .aria_haspopup(AriaHasPopup::Menu)
+                    .aria_pressed(AriaTristate::Mixed)
+                    .set_attr("aria-live", "loud")
+                    .set_attr("aria-hidden", "maybe")
+                    .add("Menu"),
+            )
+            .add(
+                span()
+                    .role("custom")
+                    .aria_current(AriaCurrent::Page)
+                    .aria_label("Home"),
+            )
+        "#);
+    }
+
+    #[test]
+    fn html_to_fhtmx_svg() {
+        let res = html_to_fhtmx(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"><path stroke-linecap="round" d="M6 18L18 6"/><linearGradient id="g"></linearGradient></svg>"#,
+        )
+        .unwrap();
+        insta::assert_snapshot!(res, @r#"
+        svg()
+            .view_box("0 0 24 24")
+            .fill("none")
+            .add(svg_path().stroke_linecap("round").d("M6 18L18 6"))
+            .add(svg_linear_gradient().id("g"))
+        "#);
+    }
+
+    #[test]
+    fn html_to_fhtmx_custom_elements() {
+        let res =
+            html_to_fhtmx(r#"<x-chart data-src="/stats"><div>Loading</div></x-chart>"#).unwrap();
+        insta::assert_snapshot!(res, @r#"
+        custom_element("x-chart")
+            .data("src", "/stats")
+            .add(div().add("Loading"))
+        "#);
+    }
+}
@@ -1,40 +1,254 @@
 use crate::{
     attribute::{AttributeValue, IntoAttributeValue},
-    element::{Element, set_attr, set_empty_attr},
-    node::{HtmlNode, IntoNode},
+    element::{Element, set_attr, set_empty_attr, set_url_attr},
+    node::{HtmlNode, IntoNode, clone_nodes, drop_nodes},
+    safe_url::IntoUrlValue,
 };
 use indexmap::{IndexMap, IndexSet};
 use pastey::paste;
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt};
 
 pub const VOID_ELEMENTS: &[&str] = &[
     "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
     "track", "wbr",
 ];
 
+/// Elements whose content is raw text: it's not escaped and can't contain their end tag
+pub const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
 pub const INLINE_ELEMENTS: &[&str] = &[
     "a", "abbr", "b", "bdo", "br", "button", "cite", "code", "em", "i", "img", "input", "kbd",
     "label", "q", "s", "samp", "select", "small", "span", "strong", "sub", "sup", "textarea",
     "time", "u", "var",
 ];
 
+/// Every html element with a constructor in this crate
+pub const HTML_ELEMENTS: &[&str] = &[
+    "a",
+    "abbr",
+    "address",
+    "area",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "base",
+    "bdi",
+    "bdo",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "canvas",
+    "caption",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "data",
+    "datalist",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "embed",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "i",
+    "iframe",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "label",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "map",
+    "mark",
+    "menu",
+    "meta",
+    "meter",
+    "nav",
+    "noscript",
+    "object",
+    "ol",
+    "optgroup",
+    "option",
+    "output",
+    "p",
+    "param",
+    "picture",
+    "pre",
+    "progress",
+    "q",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "script",
+    "search",
+    "section",
+    "select",
+    "slot",
+    "small",
+    "source",
+    "span",
+    "strong",
+    "style",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "textarea",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "title",
+    "tr",
+    "track",
+    "u",
+    "ul",
+    "var",
+    "video",
+    "wbr",
+];
+
 /// Represents a HTML element
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct HtmlElement {
-    pub tag: &'static str,
+    pub tag: Cow<'static, str>,
     pub attrs: IndexMap<Cow<'static, str>, AttributeValue>,
     pub classes: IndexSet<Cow<'static, str>>,
+    pub(crate) styles: IndexMap<Cow<'static, str>, String>,
     pub children: Vec<HtmlNode>,
 }
 
+/// Reserved names that can't be used by custom elements
+pub const RESERVED_CUSTOM_ELEMENT_NAMES: &[&str] = &[
+    "annotation-xml",
+    "color-profile",
+    "font-face",
+    "font-face-format",
+    "font-face-name",
+    "font-face-src",
+    "font-face-uri",
+    "missing-glyph",
+];
+
+/// Error of `HtmlElement::try_new` and `SvgElement::try_new`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTagName(pub String);
+
+impl fmt::Display for InvalidTagName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid tag name `{}`", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTagName {}
+
+/// Checks if `name` can be used as a tag name: it starts with an ascii letter followed by ascii
+/// alphanumerics, `-`, `.`, `_`, `:` or non ascii characters
+pub fn is_valid_tag_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|o| o.is_ascii_alphabetic())
+        && chars.all(|o| {
+            o.is_ascii_alphanumeric() || matches!(o, '-' | '.' | '_' | ':') || !o.is_ascii()
+        })
+}
+
+/// Checks if `name` is a valid custom element name (eg: `x-chart`): a lowercase tag name with a
+/// `-` that isn't reserved
+pub fn is_valid_custom_element_name(name: &str) -> bool {
+    is_valid_tag_name(name)
+        && name.contains('-')
+        && !name.contains(':')
+        && !name.chars().any(|o| o.is_ascii_uppercase())
+        && !RESERVED_CUSTOM_ELEMENT_NAMES.contains(&name)
+}
+
 impl HtmlElement {
-    pub fn new(tag: &'static str) -> Self {
+    /// Clones the element without its children
+    pub(crate) fn clone_shell(&self) -> Self {
         Self {
+            tag: self.tag.clone(),
+            attrs: self.attrs.clone(),
+            classes: self.classes.clone(),
+            styles: self.styles.clone(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Clones the children without recursion
+impl Clone for HtmlElement {
+    fn clone(&self) -> Self {
+        let mut el = self.clone_shell();
+        el.children = clone_nodes(&self.children);
+        el
+    }
+}
+
+/// Drops the children without recursion
+impl Drop for HtmlElement {
+    fn drop(&mut self) {
+        drop_nodes(&mut self.children);
+    }
+}
+
+impl HtmlElement {
+    /// Creates an element with the given tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` is not a valid tag name, see `is_valid_tag_name`.
+    #[track_caller]
+    pub fn new(tag: impl Into<Cow<'static, str>>) -> Self {
+        match Self::try_new(tag) {
+            Ok(o) => o,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Creates an element with the given tag, fails if `tag` is not a valid tag name.
+    pub fn try_new(tag: impl Into<Cow<'static, str>>) -> Result<Self, InvalidTagName> {
+        let tag = tag.into();
+        if !is_valid_tag_name(&tag) {
+            return Err(InvalidTagName(tag.into_owned()));
+        }
+        Ok(Self {
             tag,
             attrs: IndexMap::new(),
             classes: IndexSet::new(),
+            styles: IndexMap::new(),
             children: Vec::new(),
-        }
+        })
     }
 }
 
@@ -52,8 +266,8 @@ impl IntoHtmlElement for HtmlElement {
 
 impl Element for HtmlElement {
     #[inline]
-    fn tag(&self) -> &'static str {
-        self.tag
+    fn tag(&self) -> &str {
+        &self.tag
     }
 
     #[inline]
@@ -76,6 +290,16 @@ impl Element for HtmlElement {
         &mut self.classes
     }
 
+    #[inline]
+    fn styles(&self) -> Option<&IndexMap<Cow<'static, str>, String>> {
+        Some(&self.styles)
+    }
+
+    #[inline]
+    fn styles_mut(&mut self) -> Option<&mut IndexMap<Cow<'static, str>, String>> {
+        Some(&mut self.styles)
+    }
+
     #[inline]
     fn children(&self) -> &[HtmlNode] {
         &self.children
@@ -95,6 +319,11 @@ impl Element for HtmlElement {
     fn is_inline_tag(&self) -> bool {
         INLINE_ELEMENTS.contains(&self.tag())
     }
+
+    #[inline]
+    fn is_raw_text_tag(&self) -> bool {
+        RAW_TEXT_ELEMENTS.contains(&self.tag())
+    }
 }
 
 impl<T: IntoHtmlElement> IntoNode for T {
@@ -215,6 +444,7 @@ create_tag_fn!(
     search; "Defines a search section",
     section; "Defines a section in a document",
     select; "Defines a drop-down list",
+    slot; "Defines a placeholder inside a web component filled with the markup of its children",
     small; "Defines smaller text",
     source; "Defines multiple media resources for media elements (<video> and <audio>)",
     span; "Defines a section in a document",
@@ -248,11 +478,50 @@ pub fn main_tag() -> HtmlElement {
     HtmlElement::new("main")
 }
 
+/// Creates a custom element (eg: a web component).
+/// Example: `custom_element("x-chart").set_attr("data-src", "/stats")`
+///
+/// # Panics
+///
+/// Panics if `name` is not a valid custom element name, see `is_valid_custom_element_name`.
+#[track_caller]
+pub fn custom_element(name: impl Into<Cow<'static, str>>) -> HtmlElement {
+    let name = name.into();
+    if !is_valid_custom_element_name(&name) {
+        panic!("invalid custom element name `{name}`");
+    }
+    HtmlElement::new(name)
+}
+
+/// Values of the `shadowrootmode` attribute of a `template`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowRootMode {
+    Open,
+    Closed,
+}
+
+impl fmt::Display for ShadowRootMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ShadowRootMode::Open => "open",
+            ShadowRootMode::Closed => "closed",
+        })
+    }
+}
+
+impl IntoAttributeValue for ShadowRootMode {
+    fn into_attr(self) -> Option<AttributeValue> {
+        Some(AttributeValue::Value(self.to_string()))
+    }
+}
+
 impl HtmlElement {
     set_attr!(
+        accept,
         accesskey,
         alt,
         contenteditable,
+        datetime,
         decoding,
         data_tip = "data-tip",
         dir,
@@ -260,7 +529,6 @@ impl HtmlElement {
         enterkeyhint,
         for_ = "for",
         height,
-        href,
         id,
         inputmode,
         lang,
@@ -277,8 +545,6 @@ impl HtmlElement {
         role,
         sizes,
         spellcheck,
-        src,
-        srcset,
         step,
         style,
         tabindex,
@@ -290,11 +556,35 @@ impl HtmlElement {
         width
     );
 
+    set_url_attr!(URL_ATTR_SETTERS: action, cite, formaction, href, poster, src, srcset);
+
     set_empty_attr!(
-        autofocus, blocking, checked, defer, disabled, hidden, inert, multiple, nomodule, open,
-        popover, r#async, readonly, required, selected
+        autofocus,
+        blocking,
+        checked,
+        defer,
+        disabled,
+        hidden,
+        inert,
+        multiple,
+        nomodule,
+        open,
+        popover,
+        r#async,
+        readonly,
+        required,
+        selected,
+        shadowrootclonable,
+        shadowrootdelegatesfocus,
+        shadowrootserializable
     );
 
+    /// Sets the `shadowrootmode` attribute, making a `template` a declarative shadow root.
+    /// Example: `custom_element("x-card").add(template().shadowrootmode(ShadowRootMode::Open))`
+    pub fn shadowrootmode(self, mode: ShadowRootMode) -> Self {
+        self.set_attr("shadowrootmode", mode)
+    }
+
     /// Selects all texts when the element is focused (eg: useful for a search field)
     pub fn select_onfocus(self) -> Self {
         self.set_attr("onfocus", "this.select()")
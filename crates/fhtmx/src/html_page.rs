@@ -3,13 +3,14 @@ use crate::{
     html_element::*,
     node::{HtmlNode, IntoNode},
     prelude::{AttributeValue, IntoAttributeValue},
-    render::Render,
+    render::{Render, RenderOptions},
 };
 
 #[derive(Clone, Debug)]
 pub struct HtmlPage {
     doctype: bool,
     custom_html_node: Option<HtmlElement>,
+    lang: Option<String>,
     title: Option<String>,
     description: Option<String>,
     meta_charset: Option<AttributeValue>,
@@ -23,6 +24,7 @@ impl HtmlPage {
         Self {
             doctype: true,
             custom_html_node: None,
+            lang: None,
             title: None,
             description: None,
             meta_charset: "UTF-8".into_attr(),
@@ -37,6 +39,12 @@ impl HtmlPage {
         self
     }
 
+    /// Sets the `lang` of the `html` element
+    pub fn lang(mut self, lang: impl ToString) -> Self {
+        self.lang = Some(lang.to_string());
+        self
+    }
+
     pub fn title(mut self, title: impl ToString) -> Self {
         self.title = Some(title.to_string());
         self
@@ -72,6 +80,11 @@ impl HtmlPage {
     pub fn render(self) -> String {
         self.into_node().render()
     }
+
+    /// Renders the page using the given options
+    pub fn render_with(self, opts: &RenderOptions) -> String {
+        self.into_node().render_with(opts)
+    }
 }
 
 impl Default for HtmlPage {
@@ -86,7 +99,10 @@ impl IntoNode for HtmlPage {
         if self.doctype {
             nodes.push(HtmlNode::Doctype);
         }
-        let html_node = self.custom_html_node.unwrap_or_else(html);
+        let mut html_node = self.custom_html_node.unwrap_or_else(html);
+        if let Some(lang) = self.lang {
+            html_node = html_node.lang(lang);
+        }
         let mut header = head();
         if let Some(page_title) = self.title {
             header = header.add(title().add(page_title));
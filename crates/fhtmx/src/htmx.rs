@@ -1,7 +1,8 @@
 use crate::{
     attribute::{AttributeValue, IntoAttributeValue},
-    element::Element,
+    element::{Element, set_url_attr},
     html_element::HtmlElement,
+    safe_url::IntoUrlValue,
 };
 use pastey::paste;
 
@@ -187,9 +188,8 @@ impl std::fmt::Display for HtmxResponseHeader {
     }
 }
 
-// TODO: simplify
 macro_rules! set_htmx_attr {
-    ($attr:ident = $name:expr; $eg:expr) => {
+    (@setter $attr:ident = $name:expr; $eg:expr) => {
         paste! {
             #[doc = "Sets the `" $name "` attribute.\nExample: `" $eg "`"]
             pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
@@ -198,7 +198,7 @@ macro_rules! set_htmx_attr {
         }
     };
 
-    ($attr:ident = $name:expr) => {
+    (@setter $attr:ident = $name:expr) => {
         paste! {
             #[doc = "Sets the `" $name "` attribute."]
             pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
@@ -207,7 +207,7 @@ macro_rules! set_htmx_attr {
         }
     };
 
-    ($attr:ident) => {
+    (@setter $attr:ident) => {
         paste! {
             #[doc = "Sets the `" $attr "` attribute."]
             pub fn $attr(self, value: impl IntoAttributeValue) -> Self {
@@ -216,9 +216,13 @@ macro_rules! set_htmx_attr {
         }
     };
 
-    ($attr:ident$(=$name:expr)?$(;$eg:expr)?, $($rest:ident$(=$name_rest:expr)?$(;$eg_rest:expr)?),+) => {
-        set_htmx_attr!($attr$(=$name)?$(;$eg)?);
-        set_htmx_attr!($($rest$(=$name_rest)?$(;$eg_rest)?),+);
+    ($($attr:ident$(=$name:expr)?$(;$eg:expr)?),+ $(,)?) => {
+        $(set_htmx_attr!(@setter $attr$(=$name)?$(;$eg)?);)+
+
+        /// Setters defined with `set_htmx_attr!` as `(method, attribute)` pairs
+        pub(crate) const HTMX_ATTR_SETTERS: &[(&str, &str)] = &[
+            $((stringify!($attr), $crate::element::attr_name!($attr$(=$name)?))),+
+        ];
     };
 }
 
@@ -227,11 +231,9 @@ impl HtmlElement {
     set_htmx_attr!(
         hx_boost = "hx-boost"; r#"a().hx_boost("true")"#,
         hx_confirm = "hx-confirm",
-        hx_delete = "hx-delete",
         hx_disable = "hx-disable",
         hx_disabled_elt = "hx-disabled-elt",
         hx_ext = "hx-ext",
-        hx_get = "hx-get",
         hx_headers = "hx-headers",
         hx_history = "hx-history",
         hx_history_elt = "hx-history-elt",
@@ -239,12 +241,9 @@ impl HtmlElement {
         hx_indicator = "hx-indicator",
         hx_inherit = "hx-inherit",
         hx_params = "hx-params",
-        hx_patch = "hx-patch",
-        hx_post = "hx-post",
         hx_preserve = "hx-preserve",
         hx_prompt = "hx-prompt",
         hx_push_url = "hx-push-url",
-        hx_put = "hx-put",
         hx_replace_url = "hx-replace-url",
         hx_request = "hx-request",
         hx_select = "hx-select",
@@ -256,11 +255,20 @@ impl HtmlElement {
         hx_trigger = "hx-trigger",
         hx_validate = "hx-validate",
         hx_vals = "hx-vals"; r##"div().hx_vals(format!(r#"{{"key": "{x}"}}"#))"##,
-        sse_connect = "sse-connect",
         sse_swap = "sse-swap",
-        ws_connect = "ws-connect",
         ws_send = "ws-send"
     );
+
+    set_url_attr!(
+        HTMX_URL_ATTR_SETTERS:
+        hx_delete = "hx-delete",
+        hx_get = "hx-get"; eg = r#"button().hx_get("/items")"#,
+        hx_patch = "hx-patch",
+        hx_post = "hx-post",
+        hx_put = "hx-put",
+        sse_connect = "sse-connect",
+        ws_connect = "ws-connect"
+    );
 }
 
 #[cfg(test)]
@@ -1,33 +1,65 @@
+// Lets the macros of `fhtmx_derive` expand to `::fhtmx` paths inside this crate
+extern crate self as fhtmx;
+
+mod a11y;
+mod aria;
+#[cfg(feature = "serde")]
+mod ast;
 mod attribute;
+mod class_merge;
 mod components;
 mod element;
+mod entities;
+mod html2fhtmx;
 mod html_element;
 mod html_page;
 mod html_view;
 mod htmx;
 mod js;
 mod node;
+mod parse;
+mod prerender;
 mod render;
+mod safe_url;
+mod sanitize;
+mod select;
 mod sources;
+mod style;
 mod svg;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 mod url_query;
 mod utils;
+mod validate;
+mod walk;
 
 pub mod prelude {
+    pub use crate::a11y::*;
+    pub use crate::aria::*;
     pub use crate::attribute::*;
     pub use crate::children;
+    pub use crate::class_merge::*;
     pub use crate::components::*;
     pub use crate::element::*;
     pub use crate::html_element::*;
     pub use crate::html_page::*;
     pub use crate::html_view::*;
+    pub use crate::html2fhtmx::*;
     pub use crate::htmx::*;
     pub use crate::js::*;
     pub use crate::node::*;
+    pub use crate::parse::*;
+    pub use crate::prerender::*;
     pub use crate::render::*;
+    pub use crate::safe_url::*;
+    pub use crate::sanitize::*;
+    pub use crate::select::*;
     pub use crate::sources::*;
+    pub use crate::style::*;
     pub use crate::svg::*;
     pub use crate::url_query::*;
     pub use crate::utils::*;
-    pub use fhtmx_derive::HtmlView;
+    pub use crate::validate::*;
+    pub use crate::walk::*;
+    pub use fhtmx_derive::{HtmlView, component, html, rsx};
 }
@@ -1,8 +1,10 @@
-use crate::{html_element::HtmlElement, svg::SvgElement};
+use crate::{html_element::HtmlElement, prerender::Prerendered, svg::SvgElement};
+use std::slice;
 use uuid::Uuid;
 
-/// Types of nodes that can go inside an `Element`
-#[derive(Clone, Debug)]
+/// Types of nodes that can go inside an `Element`. Nodes are cloned, and elements dropped,
+/// without recursion (fragments included), so very deep trees don't overflow the stack.
+#[derive(Debug)]
 pub enum HtmlNode {
     Doctype,
     Raw(String),
@@ -10,6 +12,8 @@ pub enum HtmlNode {
     Element(HtmlElement),
     SvgElement(SvgElement),
     Fragment(Vec<HtmlNode>),
+    /// Html rendered ahead of time, see `HtmlNode::freeze`
+    Prerendered(Prerendered),
 }
 
 impl HtmlNode {
@@ -26,6 +30,34 @@ impl HtmlNode {
             _ => None,
         }
     }
+
+    pub fn as_element(&self) -> Option<&HtmlElement> {
+        match self {
+            Self::Element(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_element_mut(&mut self) -> Option<&mut HtmlElement> {
+        match self {
+            Self::Element(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_svg(&self) -> Option<&SvgElement> {
+        match self {
+            Self::SvgElement(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_svg_mut(&mut self) -> Option<&mut SvgElement> {
+        match self {
+            Self::SvgElement(x) => Some(x),
+            _ => None,
+        }
+    }
 }
 
 /// Creates a `HtmlNode::Raw` node
@@ -110,6 +142,93 @@ macro_rules! children {
     };
 }
 
+impl Clone for HtmlNode {
+    fn clone(&self) -> Self {
+        match self {
+            HtmlNode::Doctype => HtmlNode::Doctype,
+            HtmlNode::Raw(x) => HtmlNode::Raw(x.clone()),
+            HtmlNode::Text(x) => HtmlNode::Text(x.clone()),
+            HtmlNode::Prerendered(x) => HtmlNode::Prerendered(x.clone()),
+            node => clone_nodes(slice::from_ref(node))
+                .pop()
+                .unwrap_or(HtmlNode::Fragment(Vec::new())),
+        }
+    }
+}
+
+/// A node being cloned, without its children
+enum Shell {
+    Root,
+    Element(HtmlElement),
+    SvgElement(SvgElement),
+    Fragment,
+}
+
+/// Clones the nodes without recursion
+pub(crate) fn clone_nodes(nodes: &[HtmlNode]) -> Vec<HtmlNode> {
+    // Each frame has the nodes left to clone, their clones and the node that gets them
+    let mut frames = vec![(nodes.iter(), Vec::with_capacity(nodes.len()), Shell::Root)];
+    loop {
+        let Some((src, out, _)) = frames.last_mut() else {
+            return Vec::new();
+        };
+        let Some(node) = src.next() else {
+            let Some((_, children, shell)) = frames.pop() else {
+                return Vec::new();
+            };
+            let node = match shell {
+                Shell::Root => return children,
+                Shell::Element(mut el) => {
+                    el.children = children;
+                    HtmlNode::Element(el)
+                }
+                Shell::SvgElement(mut el) => {
+                    el.children = children;
+                    HtmlNode::SvgElement(el)
+                }
+                Shell::Fragment => HtmlNode::Fragment(children),
+            };
+            if let Some((_, out, _)) = frames.last_mut() {
+                out.push(node);
+            }
+            continue;
+        };
+        let (children, shell) = match node {
+            HtmlNode::Element(el) => (&el.children, Shell::Element(el.clone_shell())),
+            HtmlNode::SvgElement(el) => (&el.children, Shell::SvgElement(el.clone_shell())),
+            HtmlNode::Fragment(nodes) => (nodes, Shell::Fragment),
+            leaf => {
+                out.push(leaf.clone());
+                continue;
+            }
+        };
+        frames.push((children.iter(), Vec::with_capacity(children.len()), shell));
+    }
+}
+
+/// Drops the nodes without recursion, moving the children of the elements (and fragments) to a
+/// heap stack before they are dropped
+pub(crate) fn drop_nodes(nodes: &mut Vec<HtmlNode>) {
+    let has_grandchildren = nodes.iter().any(|o| match o {
+        HtmlNode::Element(x) => !x.children.is_empty(),
+        HtmlNode::SvgElement(x) => !x.children.is_empty(),
+        HtmlNode::Fragment(x) => !x.is_empty(),
+        _ => false,
+    });
+    if !has_grandchildren {
+        return;
+    }
+    let mut stack = std::mem::take(nodes);
+    while let Some(mut node) = stack.pop() {
+        match &mut node {
+            HtmlNode::Element(x) => stack.append(&mut x.children),
+            HtmlNode::SvgElement(x) => stack.append(&mut x.children),
+            HtmlNode::Fragment(x) => stack.append(x),
+            _ => {}
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
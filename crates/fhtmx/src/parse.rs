@@ -0,0 +1,701 @@
+use crate::{
+    attribute::AttributeValue,
+    element::Element,
+    html_element::{HTML_ELEMENTS, HtmlElement, RAW_TEXT_ELEMENTS, VOID_ELEMENTS},
+    node::HtmlNode,
+    svg::{SVG_ELEMENTS, SvgElement},
+    utils::unescape_html,
+};
+use std::fmt;
+
+/// Elements whose content is text with character references but no tags
+const ESCAPABLE_RAW_TEXT_ELEMENTS: &[&str] = &["textarea", "title"];
+
+/// Elements where whitespace is meaningful
+const PREFORMATTED_ELEMENTS: &[&str] = &["pre", "textarea"];
+
+/// Start tags that close an open `p` element
+pub(crate) const CLOSES_P: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "table",
+    "ul",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A tag whose name isn't a valid tag name
+    UnknownTag(String),
+    /// The input ended in the middle of a tag or comment
+    UnexpectedEof,
+    /// A doctype found by `HtmlNode::parse_fragment`
+    UnexpectedDoctype,
+}
+
+/// Error returned when parsing html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Byte offset in the input where the error was found
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnknownTag(tag) => write!(f, "unknown tag `{tag}`")?,
+            ParseErrorKind::UnexpectedEof => write!(f, "unexpected end of input")?,
+            ParseErrorKind::UnexpectedDoctype => write!(f, "unexpected doctype in fragment")?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl HtmlNode {
+    /// Parses a html document into a `HtmlNode::Fragment` with all the top level nodes (including
+    /// the doctype).
+    ///
+    /// The parser is lenient like browsers are: unmatched closing tags are ignored, elements left
+    /// open are closed at the end of the input and some end tags are implied (eg: `li`, `p`,
+    /// `td`). Unknown tags (eg: `font` or custom elements) are kept as elements and comments as
+    /// `HtmlNode::Raw`. Outside of `pre` and `textarea`, whitespace-only text containing newlines
+    /// (the indentation of the source) becomes a space between inline siblings and is dropped
+    /// elsewhere.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let page = HtmlNode::parse("<!DOCTYPE html><html><body><p>Hi</p></body></html>").unwrap();
+    /// ```
+    pub fn parse(html: &str) -> Result<HtmlNode, ParseError> {
+        Parser::new(html, true).run().map(HtmlNode::Fragment)
+    }
+
+    /// Parses a html snippet, returning the node itself when it has a single root and a
+    /// `HtmlNode::Fragment` otherwise. See `HtmlNode::parse` for the parsing rules.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let card = HtmlNode::parse_fragment(r#"<div class="card"><p>Hi &amp; bye</p></div>"#)
+    ///     .unwrap()
+    ///     .to_element()
+    ///     .unwrap()
+    ///     .add_class("shadow");
+    /// ```
+    pub fn parse_fragment(html: &str) -> Result<HtmlNode, ParseError> {
+        let mut nodes = Parser::new(html, false).run()?;
+        if nodes.len() == 1 {
+            Ok(nodes.remove(0))
+        } else {
+            Ok(HtmlNode::Fragment(nodes))
+        }
+    }
+}
+
+/// Parses html dropping the unknown tags (their content is kept), used to clean untrusted html
+pub(crate) fn parse_skipping_unknown_tags(html: &str) -> Result<Vec<HtmlNode>, ParseError> {
+    let mut parser = Parser::new(html, true);
+    parser.skip_unknown_tags = true;
+    parser.run()
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    allow_doctype: bool,
+    /// Drop unknown tags keeping their content, instead of failing
+    skip_unknown_tags: bool,
+    /// Open elements (`HtmlNode::Element` or `HtmlNode::SvgElement`)
+    stack: Vec<HtmlNode>,
+    roots: Vec<HtmlNode>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str, allow_doctype: bool) -> Self {
+        Self {
+            src,
+            pos: 0,
+            allow_doctype,
+            skip_unknown_tags: false,
+            stack: Vec::new(),
+            roots: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Result<Vec<HtmlNode>, ParseError> {
+        while self.pos < self.src.len() {
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.parse_comment()?;
+            } else if rest.starts_with("</") && starts_with_alpha(&rest[2..]) {
+                self.parse_end_tag()?;
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                self.parse_declaration()?;
+            } else if rest.starts_with('<') && starts_with_alpha(&rest[1..]) {
+                self.parse_start_tag()?;
+            } else {
+                self.parse_text();
+            }
+        }
+        while !self.stack.is_empty() {
+            self.close_current();
+        }
+        collapse_indentation(&mut self.roots);
+        Ok(self.roots)
+    }
+
+    #[inline]
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            kind,
+            position: self.pos,
+        }
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<HtmlNode> {
+        match self.stack.last_mut() {
+            Some(HtmlNode::Element(el)) => &mut el.children,
+            Some(HtmlNode::SvgElement(el)) => &mut el.children,
+            _ => &mut self.roots,
+        }
+    }
+
+    fn push_node(&mut self, node: HtmlNode) {
+        self.children_mut().push(node);
+    }
+
+    fn push_text(&mut self, text: String) {
+        if let Some(HtmlNode::Text(last)) = self.children_mut().last_mut() {
+            last.push_str(&text);
+        } else {
+            self.push_node(HtmlNode::Text(text));
+        }
+    }
+
+    /// Pops the current element and adds it to its parent
+    fn close_current(&mut self) {
+        let preformatted = self.in_preformatted();
+        if let Some(mut node) = self.stack.pop() {
+            match &mut node {
+                HtmlNode::Element(el) if !preformatted => collapse_indentation(&mut el.children),
+                HtmlNode::SvgElement(el) if !preformatted => collapse_indentation(&mut el.children),
+                _ => {}
+            }
+            self.push_node(node);
+        }
+    }
+
+    fn current_tag(&self) -> Option<&str> {
+        match self.stack.last() {
+            Some(HtmlNode::Element(el)) => Some(el.tag()),
+            Some(HtmlNode::SvgElement(el)) => Some(el.tag()),
+            _ => None,
+        }
+    }
+
+    fn in_svg(&self) -> bool {
+        matches!(self.stack.last(), Some(HtmlNode::SvgElement(el)) if el.tag != "foreignObject")
+    }
+
+    fn in_preformatted(&self) -> bool {
+        self.stack.iter().any(|o| match o {
+            HtmlNode::Element(el) => PREFORMATTED_ELEMENTS.contains(&el.tag()),
+            _ => false,
+        })
+    }
+
+    fn parse_comment(&mut self) -> Result<(), ParseError> {
+        let end = self.rest()[4..]
+            .find("-->")
+            .ok_or_else(|| self.error(ParseErrorKind::UnexpectedEof))?;
+        let len = 4 + end + 3;
+        let comment = &self.rest()[..len];
+        self.push_node(HtmlNode::Raw(comment.to_string()));
+        self.pos += len;
+        Ok(())
+    }
+
+    fn parse_declaration(&mut self) -> Result<(), ParseError> {
+        let rest = self.rest();
+        if rest.starts_with("<![CDATA[") {
+            let end = rest
+                .find("]]>")
+                .ok_or_else(|| self.error(ParseErrorKind::UnexpectedEof))?;
+            self.push_node(HtmlNode::Raw(rest[..end + 3].to_string()));
+            self.pos += end + 3;
+            return Ok(());
+        }
+        let end = rest
+            .find('>')
+            .ok_or_else(|| self.error(ParseErrorKind::UnexpectedEof))?;
+        let decl = &rest[..end + 1];
+        if decl.len() > 9 && decl[..9].eq_ignore_ascii_case("<!doctype") {
+            if !self.allow_doctype {
+                return Err(self.error(ParseErrorKind::UnexpectedDoctype));
+            }
+            self.push_node(HtmlNode::Doctype);
+        } else {
+            self.push_node(HtmlNode::Raw(decl.to_string()));
+        }
+        self.pos += end + 1;
+        Ok(())
+    }
+
+    fn parse_text(&mut self) {
+        let rest = self.rest();
+        // A `<` that doesn't start a tag is just text
+        let first = rest.chars().next().map_or(1, char::len_utf8);
+        let end = rest[first..]
+            .find('<')
+            .map(|i| i + first)
+            .unwrap_or(rest.len());
+        let text = &rest[..end];
+        self.pos += end;
+        self.push_text(unescape_html(text));
+    }
+
+    fn parse_end_tag(&mut self) -> Result<(), ParseError> {
+        let rest = self.rest();
+        let end = rest
+            .find('>')
+            .ok_or_else(|| self.error(ParseErrorKind::UnexpectedEof))?;
+        let name = rest[2..end].trim_end_matches(|c: char| c.is_whitespace() || c == '/');
+        let name = name.split_whitespace().next().unwrap_or_default();
+        self.pos += end + 1;
+        let open = self.stack.iter().rposition(|o| match o {
+            HtmlNode::Element(el) => el.tag.eq_ignore_ascii_case(name),
+            HtmlNode::SvgElement(el) => el.tag.eq_ignore_ascii_case(name),
+            _ => false,
+        });
+        // Unmatched end tags are ignored
+        if let Some(i) = open {
+            while self.stack.len() > i {
+                self.close_current();
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_start_tag(&mut self) -> Result<(), ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let name = self.take_while(|c| !c.is_whitespace() && c != '/' && c != '>');
+        let in_svg = self.in_svg() || name.eq_ignore_ascii_case("svg");
+        let known = find_tag(if in_svg { SVG_ELEMENTS } else { HTML_ELEMENTS }, name);
+        let node = match known {
+            Some(tag) if in_svg => Some(HtmlNode::SvgElement(SvgElement::new(tag))),
+            Some(tag) => Some(HtmlNode::Element(HtmlElement::new(tag))),
+            None if self.skip_unknown_tags => None,
+            // Unknown tags (custom elements or legacy ones like `font`) are kept as is
+            None if in_svg => SvgElement::try_new(name.to_string())
+                .ok()
+                .map(HtmlNode::SvgElement),
+            None => HtmlElement::try_new(name.to_ascii_lowercase())
+                .ok()
+                .map(HtmlNode::Element),
+        };
+        if node.is_none() && !self.skip_unknown_tags {
+            self.pos = start;
+            return Err(self.error(ParseErrorKind::UnknownTag(name.to_string())));
+        }
+
+        let mut attrs = Vec::new();
+        let self_closing = loop {
+            self.take_while(char::is_whitespace);
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error(ParseErrorKind::UnexpectedEof));
+            } else if rest.starts_with("/>") {
+                self.pos += 2;
+                break true;
+            } else if rest.starts_with('>') {
+                self.pos += 1;
+                break false;
+            } else if rest.starts_with('/') {
+                self.pos += 1;
+                continue;
+            }
+            attrs.push(self.parse_attr(in_svg)?);
+        };
+        let Some(mut node) = node else {
+            return Ok(());
+        };
+        let tag = match &mut node {
+            HtmlNode::Element(el) => {
+                set_attrs(el, attrs);
+                el.tag.clone()
+            }
+            HtmlNode::SvgElement(el) => {
+                set_attrs(el, attrs);
+                el.tag.clone()
+            }
+            _ => unreachable!(),
+        };
+        let tag = tag.as_ref();
+        if !in_svg {
+            self.close_implied(tag);
+        }
+
+        if self_closing || (!in_svg && VOID_ELEMENTS.contains(&tag)) {
+            self.push_node(node);
+        } else if !in_svg && RAW_TEXT_ELEMENTS.contains(&tag) {
+            let content = self.take_raw_text(tag);
+            if let HtmlNode::Element(el) = &mut node
+                && !content.is_empty()
+            {
+                el.children.push(HtmlNode::Raw(content.to_string()));
+            }
+            self.push_node(node);
+        } else if !in_svg && ESCAPABLE_RAW_TEXT_ELEMENTS.contains(&tag) {
+            let content = self.take_raw_text(tag);
+            let content = content.strip_prefix('\n').unwrap_or(content);
+            if let HtmlNode::Element(el) = &mut node
+                && !content.is_empty()
+            {
+                el.children.push(HtmlNode::Text(unescape_html(content)));
+            }
+            self.push_node(node);
+        } else {
+            if tag == "pre" && self.rest().starts_with('\n') {
+                // A newline right after `<pre>` is ignored by browsers
+                self.pos += 1;
+            }
+            self.stack.push(node);
+        }
+        Ok(())
+    }
+
+    fn parse_attr(&mut self, in_svg: bool) -> Result<(String, AttributeValue), ParseError> {
+        let name = self.take_while(|c| !c.is_whitespace() && !matches!(c, '=' | '>' | '/'));
+        let name = if in_svg {
+            name.to_string()
+        } else {
+            name.to_ascii_lowercase()
+        };
+        let before_value = self.pos;
+        self.take_while(char::is_whitespace);
+        if !self.rest().starts_with('=') {
+            self.pos = before_value;
+            return Ok((name, AttributeValue::Empty));
+        }
+        self.pos += 1;
+        self.take_while(char::is_whitespace);
+        let rest = self.rest();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = rest[1..]
+                    .find(quote)
+                    .ok_or_else(|| self.error(ParseErrorKind::UnexpectedEof))?;
+                self.pos += end + 2;
+                &rest[1..end + 1]
+            }
+            Some(_) => self.take_while(|c| !c.is_whitespace() && c != '>'),
+            None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+        };
+        Ok((name, AttributeValue::Value(unescape_html(value))))
+    }
+
+    /// Takes the content of a raw text element up to its closing tag
+    fn take_raw_text(&mut self, tag: &str) -> &'a str {
+        let rest = self.rest();
+        let closing = format!("</{tag}");
+        let end = find_ignore_ascii_case(rest, &closing).unwrap_or(rest.len());
+        let content = &rest[..end];
+        self.pos += end;
+        let after = self.rest();
+        self.pos += after.find('>').map(|i| i + 1).unwrap_or(after.len());
+        content
+    }
+
+    /// Closes the elements whose end tag is implied by the start of `tag`
+    fn close_implied(&mut self, tag: &str) {
+        let Some(current) = self.current_tag() else {
+            return;
+        };
+        let closes = match current {
+            "p" => CLOSES_P.contains(&tag),
+            "li" => tag == "li",
+            "dt" | "dd" => matches!(tag, "dt" | "dd"),
+            "td" | "th" => matches!(tag, "td" | "th" | "tr"),
+            "tr" => tag == "tr",
+            "option" => matches!(tag, "option" | "optgroup"),
+            _ => false,
+        };
+        if closes {
+            self.close_current();
+            // `<tr>` after a cell closes the row too
+            if tag == "tr" && self.current_tag() == Some("tr") {
+                self.close_current();
+            }
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let end = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+}
+
+fn set_attrs<E: Element>(el: &mut E, attrs: Vec<(String, AttributeValue)>) {
+    for (k, v) in attrs {
+        match (k.as_str(), v) {
+            ("class", AttributeValue::Value(v)) => el.add_class_mut(v),
+            (_, v) => el.set_attr_mut(k, v),
+        }
+    }
+}
+
+#[inline]
+fn starts_with_alpha(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+/// Collapses the whitespace with newlines (indentation) between inline siblings to a space, and
+/// drops it next to block elements or at the start and end of its parent
+fn collapse_indentation(nodes: &mut Vec<HtmlNode>) {
+    let is_indentation =
+        |o: &HtmlNode| matches!(o, HtmlNode::Text(x) if x.trim().is_empty() && x.contains('\n'));
+    if !nodes.iter().any(is_indentation) {
+        return;
+    }
+    let is_inline = |o: Option<&HtmlNode>| match o {
+        Some(HtmlNode::Element(el)) => el.is_inline_tag(),
+        Some(HtmlNode::SvgElement(el)) => el.is_inline_tag(),
+        Some(HtmlNode::Text(_) | HtmlNode::Raw(_)) => true,
+        _ => false,
+    };
+    let mut res = Vec::with_capacity(nodes.len());
+    let mut iter = std::mem::take(nodes).into_iter().peekable();
+    while let Some(node) = iter.next() {
+        if !is_indentation(&node) {
+            res.push(node);
+        } else if is_inline(res.last()) && is_inline(iter.peek()) {
+            res.push(HtmlNode::Text(" ".to_string()));
+        }
+    }
+    *nodes = res;
+}
+
+fn find_tag(tags: &[&'static str], name: &str) -> Option<&'static str> {
+    tags.iter().find(|o| o.eq_ignore_ascii_case(name)).copied()
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|o| o.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::render::Render;
+
+    #[test]
+    fn parse_roundtrip() {
+        let html = r#"<div class="card bg-base-100" id="main">
+  <div class="card-body">
+    <h2 class="card-title">Title &amp; more</h2>
+    <p>Some <b>bold</b> text</p>
+    <input type="checkbox" checked />
+  </div>
+</div>"#;
+        let res = HtmlNode::parse_fragment(html).unwrap().render();
+        assert_eq!(res, html);
+    }
+
+    #[test]
+    fn parse_document() {
+        let res = HtmlNode::parse(
+            "<!doctype html><html><head><title>A &lt;title&gt;</title></head><body><p>Hi</body></html>",
+        )
+        .unwrap()
+        .render();
+        insta::assert_snapshot!(res, @r"
+        <!DOCTYPE html>
+        <html>
+          <head>
+            <title>A &lt;title&gt;</title>
+          </head>
+          <body>
+            <p>Hi</p>
+          </body>
+        </html>
+        ");
+    }
+
+    #[test]
+    fn parse_raw_text_and_entities() {
+        let node = HtmlNode::parse_fragment(
+            r#"<div><script>if (a < b && c) { x = "</div>"; }</script><textarea>
+a &lt; b</textarea><p title='O&#39;Brien'>&copy; 2025&nbsp;&unknown;</p></div>"#,
+        )
+        .unwrap();
+        let el = node.to_element().unwrap();
+        assert!(matches!(
+            el.get_child(0).unwrap(),
+            HtmlNode::Element(x) if matches!(&x.children[..], [HtmlNode::Raw(s)] if s == r#"if (a < b && c) { x = "</div>"; }"#)
+        ));
+        assert!(matches!(
+            el.get_child(1).unwrap(),
+            HtmlNode::Element(x) if matches!(&x.children[..], [HtmlNode::Text(s)] if s == "a < b")
+        ));
+        assert!(matches!(
+            el.get_child(2).unwrap(),
+            HtmlNode::Element(x) if matches!(&x.children[..], [HtmlNode::Text(s)] if s == "© 2025\u{a0}&unknown;")
+                && matches!(x.attrs.get("title"), Some(AttributeValue::Value(v)) if v == "O'Brien")
+        ));
+    }
+
+    #[test]
+    fn parse_named_entities_roundtrip() {
+        let node = HtmlNode::parse_fragment("<p>&alpha; &hellip; &rarr; &amp;alpha;</p>").unwrap();
+        insta::assert_snapshot!(node.render(), @"<p>α … → &amp;alpha;</p>");
+    }
+
+    #[test]
+    fn parse_svg() {
+        let node = HtmlNode::parse_fragment(
+            r#"<svg viewBox="0 0 24 24"><linearGradient id="g"><stop offset="0"/></linearGradient><path d="M0 0"/></svg>"#,
+        )
+        .unwrap();
+        let res = node.to_svg().unwrap();
+        assert_eq!(res.tag, "svg");
+        insta::assert_snapshot!(res.render(), @r#"
+        <svg viewBox="0 0 24 24">
+          <linearGradient id="g">
+            <stop offset="0"></stop>
+          </linearGradient>
+          <path d="M0 0"></path>
+        </svg>
+        "#);
+    }
+
+    #[test]
+    fn parse_custom_elements() {
+        let html = r#"<my-widget data-id="1"><template shadowrootmode="open"><slot></slot></template><X-Chart></X-Chart></my-widget>"#;
+        let res = HtmlNode::parse_fragment(html).unwrap().render();
+        insta::assert_snapshot!(res, @r#"
+        <my-widget data-id="1">
+          <template shadowrootmode="open">
+            <slot></slot>
+          </template>
+          <x-chart></x-chart>
+        </my-widget>
+        "#);
+        // Dropped when cleaning untrusted html
+        let res = HtmlNode::Fragment(
+            parse_skipping_unknown_tags("<p><my-widget>Hi</my-widget></p>").unwrap(),
+        )
+        .render();
+        insta::assert_snapshot!(res, @"<p>Hi</p>");
+    }
+
+    #[test]
+    fn parse_implied_end_tags() {
+        let res = HtmlNode::parse_fragment("<ul><li>One<li>Two</ul><p>A<p>B</span>")
+            .unwrap()
+            .render();
+        insta::assert_snapshot!(res, @r"
+        <ul>
+          <li>One</li>
+          <li>Two</li>
+        </ul>
+        <p>A</p>
+        <p>B</p>
+        ");
+    }
+
+    #[test]
+    fn parse_keeps_inline_whitespace_and_comments() {
+        let res = HtmlNode::parse_fragment("<p>é <b>a</b> <i>b</i> 1 < 2<!-- note --></p>")
+            .unwrap()
+            .render();
+        insta::assert_snapshot!(res, @"<p>é <b>a</b> <i>b</i> 1 &lt; 2<!-- note --></p>");
+    }
+
+    #[test]
+    fn parse_collapses_indentation_between_inline_elements() {
+        let res = HtmlNode::parse_fragment("<p>\n  <b>Hello</b>\n  <i>world</i>\n</p>")
+            .unwrap()
+            .render();
+        insta::assert_snapshot!(res, @"<p><b>Hello</b> <i>world</i></p>");
+        let res = HtmlNode::parse_fragment("<div>\n  <span>a</span>\n  <p>b</p>\n</div>")
+            .unwrap()
+            .render();
+        insta::assert_snapshot!(res, @r"
+        <div>
+          <span>a</span>
+          <p>b</p>
+        </div>
+        ");
+    }
+
+    #[test]
+    fn parse_unknown_tags() {
+        let res = HtmlNode::parse_fragment(
+            "<center><font color=red>Hi</font></center><math><mi>x</mi></math>",
+        )
+        .unwrap()
+        .render();
+        insta::assert_snapshot!(res, @r#"
+        <center>
+          <font color="red">Hi</font>
+        </center>
+        <math>
+          <mi>x</mi>
+        </math>
+        "#);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = HtmlNode::parse_fragment("<div><a<b></a<b></div>").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownTag("a<b".to_string()));
+        assert_eq!(err.position, 5);
+        let err = HtmlNode::parse_fragment("<div class=\"x").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEof);
+        let err = HtmlNode::parse_fragment("<!DOCTYPE html><p></p>").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedDoctype);
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{
+    element::Element,
+    node::{HtmlNode, IntoNode},
+    render::{Indent, Render, RenderOptions},
+};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+/// Html rendered ahead of time, spliced into later renders at their indentation.
+/// Created with `HtmlNode::freeze` or a `FragmentCache`, cloning it is cheap.
+///
+/// Renders using other options than the ones it was rendered with (eg: a compact or XML render)
+/// render its node again instead of splicing it.
+#[derive(Debug, Clone)]
+pub struct Prerendered {
+    html: Arc<str>,
+    /// Lines that are not indented when splicing, as the renderer keeps them as they are (eg:
+    /// the content of a `pre`), sorted
+    verbatim_lines: Arc<[usize]>,
+    /// Whether it was a block element, so its parent lays out its children as blocks
+    block: bool,
+    /// The node and the options it was rendered with, missing when deserialized
+    source: Option<Arc<(HtmlNode, RenderOptions)>>,
+}
+
+impl Prerendered {
+    pub(crate) fn new(
+        html: impl Into<Arc<str>>,
+        mut verbatim_lines: Vec<usize>,
+        block: bool,
+    ) -> Self {
+        verbatim_lines.sort_unstable();
+        verbatim_lines.dedup();
+        Self {
+            html: html.into(),
+            verbatim_lines: verbatim_lines.into(),
+            block,
+            source: None,
+        }
+    }
+
+    /// Renders the node with the given options
+    pub fn render_node(node: &HtmlNode, opts: &RenderOptions) -> Self {
+        let html = node.render_with(opts);
+        let verbatim_lines = find_verbatim_lines(node, &html, opts);
+        let block = match node {
+            HtmlNode::Element(x) => !x.is_inline_tag(),
+            HtmlNode::SvgElement(x) => !x.is_inline_tag(),
+            HtmlNode::Prerendered(x) => x.block,
+            _ => false,
+        };
+        Self {
+            source: Some(Arc::new((node.clone(), *opts))),
+            ..Self::new(html, verbatim_lines, block)
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.html
+    }
+
+    /// Indexes of the lines that are not indented when splicing
+    pub fn verbatim_lines(&self) -> &[usize] {
+        &self.verbatim_lines
+    }
+
+    pub fn is_block(&self) -> bool {
+        self.block
+    }
+
+    /// Options it was rendered with, unknown when deserialized
+    pub fn options(&self) -> Option<&RenderOptions> {
+        self.source.as_deref().map(|(_, opts)| opts)
+    }
+
+    /// The node to render instead of splicing the html, when it was rendered with other options
+    pub(crate) fn live_node(&self, opts: &RenderOptions) -> Option<&HtmlNode> {
+        match self.source.as_deref() {
+            Some((node, x)) if x != opts => Some(node),
+            _ => None,
+        }
+    }
+
+    /// Writes the html with each line indented, except the verbatim ones
+    pub(crate) fn write_indented<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        indent: usize,
+        opts: &RenderOptions,
+    ) -> fmt::Result {
+        if indent == 0 || !opts.newline {
+            return w.write_str(&self.html);
+        }
+        for (i, line) in self.html.split('\n').enumerate() {
+            if i > 0 {
+                w.write_char('\n')?;
+            }
+            if self.verbatim_lines.binary_search(&i).is_err() {
+                opts.write_indent(w, indent)?;
+            }
+            w.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the node rendered at indentation 0 (`html`) and 1 to find the lines the renderer
+/// doesn't indent (whitespace-sensitive or multiline inline content)
+fn find_verbatim_lines(node: &HtmlNode, html: &str, opts: &RenderOptions) -> Vec<usize> {
+    if !opts.newline || opts.indent == Indent::None || !html.contains('\n') {
+        return Vec::new();
+    }
+    let mut pad = String::new();
+    opts.write_indent(&mut pad, 1).ok();
+    let mut indented = String::with_capacity(html.len() * 2);
+    node.render_to_with(&mut indented, 1, opts);
+    html.split('\n')
+        .zip(indented.split('\n'))
+        .enumerate()
+        .filter(|(_, (line, indented))| indented.strip_prefix(pad.as_str()) != Some(*line))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Compares the rendered html
+impl PartialEq for Prerendered {
+    fn eq(&self, other: &Self) -> bool {
+        self.html == other.html
+            && self.verbatim_lines == other.verbatim_lines
+            && self.block == other.block
+    }
+}
+
+impl Eq for Prerendered {}
+
+impl HtmlNode {
+    /// Renders the node once with the default options, the result can be stored and added to
+    /// other elements without rendering it again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let navbar = nav().add(a().href("/").add("Home")).into_node().freeze();
+    /// let page = body().add(navbar.clone()).add(main_tag().add("Content"));
+    /// ```
+    pub fn freeze(&self) -> Self {
+        self.freeze_with(&RenderOptions::default())
+    }
+
+    /// Same as `freeze` with the given render options, renders with other options render the
+    /// node again
+    pub fn freeze_with(&self, opts: &RenderOptions) -> Self {
+        match self {
+            HtmlNode::Prerendered(_) => self.clone(),
+            node => HtmlNode::Prerendered(Prerendered::render_node(node, opts)),
+        }
+    }
+}
+
+impl IntoNode for Prerendered {
+    fn into_node(self) -> HtmlNode {
+        HtmlNode::Prerendered(self)
+    }
+}
+
+/// Keyed cache of pre-rendered fragments, to skip the render work of the parts of a page that
+/// don't change (eg: navbars, icons or inline css). It can be shared between threads.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// use std::sync::LazyLock;
+///
+/// static CACHE: LazyLock<FragmentCache> = LazyLock::new(FragmentCache::new);
+///
+/// fn page(content: &str) -> HtmlElement {
+///     body()
+///         .add(CACHE.get_or_render("navbar", || nav().add(a().href("/").add("Home"))))
+///         .add(main_tag().add(content.to_string()))
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct FragmentCache {
+    entries: RwLock<HashMap<Cow<'static, str>, Prerendered>>,
+    opts: RenderOptions,
+}
+
+impl FragmentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders the fragments with the given options
+    pub fn with_options(opts: RenderOptions) -> Self {
+        Self {
+            entries: RwLock::default(),
+            opts,
+        }
+    }
+
+    /// Gets the fragment stored under `key`, building and rendering it with `f` when missing
+    pub fn get_or_render<N: IntoNode>(
+        &self,
+        key: impl Into<Cow<'static, str>>,
+        f: impl FnOnce() -> N,
+    ) -> HtmlNode {
+        let key = key.into();
+        if let Some(x) = self.read().get(key.as_ref()) {
+            return HtmlNode::Prerendered(x.clone());
+        }
+        // Rendered without holding the lock, if another thread got first its fragment is kept
+        let rendered = Prerendered::render_node(&f().into_node(), &self.opts);
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        HtmlNode::Prerendered(entries.entry(key).or_insert(rendered).clone())
+    }
+
+    /// Gets the fragment stored under `key`
+    pub fn get(&self, key: &str) -> Option<HtmlNode> {
+        self.read().get(key).cloned().map(HtmlNode::Prerendered)
+    }
+
+    /// Removes a fragment so it's rendered again on the next use, returns true if it was stored
+    pub fn invalidate(&self, key: &str) -> bool {
+        self.entries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key)
+            .is_some()
+    }
+
+    /// Removes all the fragments
+    pub fn clear(&self) {
+        self.entries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<Cow<'static, str>, Prerendered>> {
+        self.entries.read().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::html_element::*;
+    use std::cell::Cell;
+
+    fn navbar() -> HtmlElement {
+        nav().class("navbar").add(
+            ul().add(li().add(a().href("/").add("Home")))
+                .add(li().add("Some\ntext")),
+        )
+    }
+
+    #[test]
+    fn frozen_subtree_matches_live_render() {
+        let live = div().add(section().add(navbar())).add(p().add("Content"));
+        let frozen = div()
+            .add(section().add(navbar().into_node().freeze()))
+            .add(p().add("Content"));
+        assert_eq!(frozen.render(), live.render());
+        insta::assert_snapshot!(frozen.render(), @r#"
+        <div>
+          <section>
+            <nav class="navbar">
+              <ul>
+                <li><a href="/">Home</a></li>
+                <li>Some
+        text</li>
+              </ul>
+            </nav>
+          </section>
+          <p>Content</p>
+        </div>
+        "#);
+        let frozen = navbar().into_node().freeze_with(&RenderOptions::compact());
+        let res = div().add(section().add(frozen)).render_compact();
+        assert_eq!(res, live.render_compact().replace("<p>Content</p>", ""));
+    }
+
+    #[test]
+    fn frozen_preformatted_content_is_verbatim() {
+        let code_block = || div().add(pre().add("fn main() {\n    todo!()\n}"));
+        let frozen = code_block().into_node().freeze();
+        let HtmlNode::Prerendered(x) = &frozen else {
+            panic!("expected a prerendered node");
+        };
+        assert_eq!(x.verbatim_lines(), [2, 3]);
+        let live = section().add(div().add(code_block()));
+        let res = section().add(div().add(frozen)).render();
+        assert_eq!(res, live.render());
+        insta::assert_snapshot!(res, @r"
+        <section>
+          <div>
+            <div>
+              <pre>fn main() {
+            todo!()
+        }</pre>
+            </div>
+          </div>
+        </section>
+        ");
+    }
+
+    #[test]
+    fn frozen_with_other_options_renders_again() {
+        let frozen = navbar().into_node().freeze_with(&RenderOptions::compact());
+        let HtmlNode::Prerendered(x) = &frozen else {
+            panic!("expected a prerendered node");
+        };
+        assert_eq!(x.options(), Some(&RenderOptions::compact()));
+        let live = section().add(navbar());
+        let res = section().add(frozen.clone());
+        assert_eq!(res.render(), live.render());
+        assert_eq!(res.render_compact(), live.render_compact());
+        assert_eq!(res.render_xml(), live.render_xml());
+    }
+
+    #[test]
+    fn fragment_cache_renders_once() {
+        let cache = FragmentCache::new();
+        let calls = Cell::new(0);
+        let get = || {
+            cache.get_or_render("navbar", || {
+                calls.set(calls.get() + 1);
+                navbar()
+            })
+        };
+        let res = div().add(get()).add(get()).render();
+        assert_eq!(res, div().add(navbar()).add(navbar()).render());
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("navbar").is_some());
+        assert!(cache.invalidate("navbar"));
+        assert!(cache.is_empty());
+        get();
+        assert_eq!(calls.get(), 2);
+        cache.clear();
+        assert!(cache.get("navbar").is_none());
+    }
+}
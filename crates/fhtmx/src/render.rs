@@ -1,142 +1,652 @@
 use crate::{
+    attribute::AttributeValue,
+    class_merge::split_variants,
     element::Element,
     node::HtmlNode,
-    utils::{escape_html_to, escape_html_to_with_indent},
+    style::{get_style, merge_style},
+    utils::{write_cdata, write_escaped_html, write_html_lines, write_raw_text},
+    walk::{Walk, walk_nodes},
 };
+use std::{fmt, io};
+
+/// Indentation used for each nesting level when pretty-printing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    /// No indentation
+    None,
+    /// `n` spaces per level
+    Spaces(usize),
+    /// One tab per level
+    Tab,
+}
+
+/// Markup syntax written by the renderer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Syntax {
+    /// Html: only void elements are self-closed and empty attributes have no value
+    #[default]
+    Html,
+    /// Well-formed XML, for standalone svg files or XHTML: empty elements are self-closed,
+    /// attribute values are always escaped, the content of `script` and `style` goes in a CDATA
+    /// section, and the `xmlns` of `html` and `svg` (and `xmlns:xlink` when used) are declared.
+    /// Attributes whose names are not valid XML (eg: `@click` or `hx-on:click`) are skipped and
+    /// pre-rendered nodes are rendered again from their node. Raw nodes (and deserialized
+    /// pre-rendered nodes) are written as they are, so they must be valid XML.
+    Xml,
+}
+
+/// XML declaration written by `Render::render_xml`
+pub const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// Controls the whitespace inserted by the renderer and the markup syntax.
+///
+/// The default pretty-prints html with a two-space indent and newlines between block children,
+/// use `RenderOptions::compact()` to drop all inserted whitespace (eg: to reduce the size of htmx
+/// partials) and `RenderOptions::xml()` to write XML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Indentation for each nesting level, only used when `newline` is set
+    pub indent: Indent,
+    /// Inserts newlines between block children
+    pub newline: bool,
+    /// `Syntax::Html` only self-closes void elements, `Syntax::Xml` self-closes every empty
+    /// element, declares the namespaces and skips attribute names that are invalid in XML
+    pub syntax: Syntax,
+}
+
+impl RenderOptions {
+    /// Two-space indentation with newlines between block children
+    pub const fn pretty() -> Self {
+        Self {
+            indent: Indent::Spaces(2),
+            newline: true,
+            syntax: Syntax::Html,
+        }
+    }
+
+    /// No inserted whitespace: the output is exactly what the tree contains
+    pub const fn compact() -> Self {
+        Self {
+            indent: Indent::None,
+            newline: false,
+            syntax: Syntax::Html,
+        }
+    }
+
+    /// Pretty-printed XML
+    pub const fn xml() -> Self {
+        Self::pretty().syntax(Syntax::Xml)
+    }
+
+    pub const fn indent(mut self, indent: Indent) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub const fn newline(mut self, newline: bool) -> Self {
+        self.newline = newline;
+        self
+    }
+
+    pub const fn syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Writes the padding for a nesting level
+    pub(crate) fn write_indent<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        level: usize,
+    ) -> fmt::Result {
+        if !self.newline {
+            return Ok(());
+        }
+        let pad = match self.indent {
+            Indent::None => return Ok(()),
+            Indent::Spaces(n) => n,
+            Indent::Tab => 1,
+        };
+        for _ in 0..level * pad {
+            w.write_char(if self.indent == Indent::Tab {
+                '\t'
+            } else {
+                ' '
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Writes a newline if enabled
+    #[inline]
+    pub(crate) fn write_newline<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+        if self.newline {
+            w.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self::pretty()
+    }
+}
 
 /// Renders to HTML strings
 pub trait Render {
-    /// Render to a buffer
-    fn render_to(&self, buf: &mut String, indent: usize);
+    /// Render into any `std::fmt::Write` sink using the given options
+    fn write_with<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        indent: usize,
+        opts: &RenderOptions,
+    ) -> fmt::Result;
 
     /// Size hint for more efficient rendering
     fn size_hint(&self) -> usize;
 
+    /// Render into any `std::fmt::Write` sink
+    fn write_to<W: fmt::Write + ?Sized>(&self, w: &mut W, indent: usize) -> fmt::Result {
+        self.write_with(w, indent, &RenderOptions::default())
+    }
+
+    /// Render to a buffer
+    fn render_to(&self, buf: &mut String, indent: usize) {
+        self.render_to_with(buf, indent, &RenderOptions::default());
+    }
+
+    /// Render to a buffer using the given options
+    fn render_to_with(&self, buf: &mut String, indent: usize, opts: &RenderOptions) {
+        // Writing to a `String` never fails
+        let _ = self.write_with(buf, indent, opts);
+    }
+
     fn render(&self) -> String {
+        self.render_with(&RenderOptions::default())
+    }
+
+    /// Render using the given options
+    fn render_with(&self, opts: &RenderOptions) -> String {
         let mut buf = String::with_capacity(self.size_hint());
-        self.render_to(&mut buf, 0);
+        self.render_to_with(&mut buf, 0, opts);
         buf
     }
+
+    /// Render without any inserted whitespace
+    fn render_compact(&self) -> String {
+        self.render_with(&RenderOptions::compact())
+    }
+
+    /// Render as a standalone XML document (eg: a `.svg` file or XHTML), starting with the XML
+    /// declaration. To stream it, write `XML_DECLARATION` and then render with
+    /// `RenderOptions::xml()`.
+    fn render_xml(&self) -> String {
+        let mut buf = String::with_capacity(XML_DECLARATION.len() + 1 + self.size_hint());
+        buf.push_str(XML_DECLARATION);
+        buf.push('\n');
+        self.render_to_with(&mut buf, 0, &RenderOptions::xml());
+        buf
+    }
+
+    /// Overrides the options used by the other render methods, eg:
+    /// `el.with_options(RenderOptions::compact()).render_io(&mut file)`
+    fn with_options(&self, opts: RenderOptions) -> WithOptions<'_, Self>
+    where
+        Self: Sized,
+    {
+        WithOptions { inner: self, opts }
+    }
+
+    /// Render into a `std::fmt::Write` sink (eg: a `String` or a `Formatter`)
+    fn render_fmt<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+        self.write_to(w, 0)
+    }
+
+    /// Render into a `std::io::Write` sink (eg: a file or a response body) without building the
+    /// whole page in memory first.
+    ///
+    /// The renderer does many small writes, so unbuffered sinks should be wrapped in a
+    /// `std::io::BufWriter`.
+    fn render_io<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        let mut adapter = IoAdapter {
+            inner: w,
+            error: None,
+        };
+        self.write_to(&mut adapter, 0).map_err(|_| {
+            adapter
+                .error
+                .unwrap_or_else(|| io::Error::other("formatter error"))
+        })
+    }
+
+    /// Render into a `BytesMut` buffer
+    #[cfg(feature = "bytes")]
+    fn render_to_bytes(&self, buf: &mut bytes::BytesMut) {
+        buf.reserve(self.size_hint());
+        // Writing to a `BytesMut` never fails
+        let _ = self.write_to(buf, 0);
+    }
+
+    /// Render into a `Bytes` buffer
+    #[cfg(feature = "bytes")]
+    fn render_bytes(&self) -> bytes::Bytes {
+        let mut buf = bytes::BytesMut::new();
+        self.render_to_bytes(&mut buf);
+        buf.freeze()
+    }
 }
 
-impl<T: Element> Render for T {
-    fn render_to(&self, buf: &mut String, indent: usize) {
-        // TODO: Convert recursion to iteration using an explicit stack:
+/// Renders the inner value with fixed options, see `Render::with_options`
+#[derive(Debug, Clone, Copy)]
+pub struct WithOptions<'a, T> {
+    inner: &'a T,
+    opts: RenderOptions,
+}
 
-        let tag = self.tag();
-        let pad = "  ".repeat(indent);
+impl<T: Render> Render for WithOptions<'_, T> {
+    fn write_with<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        indent: usize,
+        _opts: &RenderOptions,
+    ) -> fmt::Result {
+        self.inner.write_with(w, indent, &self.opts)
+    }
 
-        buf.push_str(&pad);
-        buf.push('<');
-        buf.push_str(tag);
+    fn size_hint(&self) -> usize {
+        self.inner.size_hint()
+    }
+}
+
+/// Bridges `std::fmt::Write` into `std::io::Write`, keeping the underlying io error
+struct IoAdapter<'a, W: io::Write + ?Sized> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write + ?Sized> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Pending work for the renderer, processed from an explicit stack so deeply nested trees don't
+/// overflow the call stack
+enum Task<'a> {
+    /// Renders a node at an indentation level
+    Node(&'a HtmlNode, usize, Flow<'a>),
+    /// Writes a newline (if enabled)
+    Newline,
+    /// Writes a closing tag, padded when closing block content
+    Close(&'a str, Option<usize>),
+    /// Writes a string as it is
+    Str(&'static str),
+}
 
-        if !self.classes().is_empty() {
-            buf.push_str(" class=\"");
-            for o in self.classes() {
-                escape_html_to(o, buf);
-                buf.push(' ');
+/// How the renderer lays out a node
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flow<'a> {
+    /// Indented as set by the `RenderOptions`
+    Indented,
+    /// As it is, without adding any whitespace (inside whitespace-sensitive elements)
+    Verbatim,
+    /// Unescaped content of a raw text element (`script` or `style`), escaping its end tag
+    RawText(&'a str),
+    /// Unescaped content of a CDATA section (raw text elements in XML)
+    CData,
+}
+
+/// Elements whose content whitespace is meaningful. Browsers also drop a newline right after
+/// their opening tag.
+const PREFORMATTED_ELEMENTS: &[&str] = &["listing", "pre", "textarea"];
+
+/// Tailwind classes setting a `white-space` that keeps spaces or newlines
+const WHITESPACE_PRE_CLASSES: &[&str] = &[
+    "whitespace-break-spaces",
+    "whitespace-pre",
+    "whitespace-pre-line",
+    "whitespace-pre-wrap",
+];
+
+/// Checks if the content of an element must be rendered as it is: `pre` and `textarea`, and
+/// elements with a `white-space: pre*` class (with any variant) or style
+fn preserves_whitespace<E: Element>(el: &E) -> bool {
+    if PREFORMATTED_ELEMENTS.contains(&el.tag())
+        || el
+            .classes()
+            .iter()
+            .any(|o| WHITESPACE_PRE_CLASSES.contains(&split_variants(o).1))
+    {
+        return true;
+    }
+    get_style(el, "white-space").is_some_and(|o| o.starts_with("pre") || o == "break-spaces")
+}
+
+/// Writes the value of an attribute for the syntax in `opts`
+fn write_attr_value<W: fmt::Write + ?Sized>(
+    v: &AttributeValue,
+    w: &mut W,
+    opts: &RenderOptions,
+) -> fmt::Result {
+    match opts.syntax {
+        Syntax::Html => v.write_to(w),
+        Syntax::Xml => v.write_xml_to(w),
+    }
+}
+
+fn has_xlink_attr<E: Element>(el: &E) -> bool {
+    el.attrs().keys().any(|k| k.starts_with("xlink:"))
+}
+
+/// Checks that an attribute name is valid in namespaced XML: without a prefix or with one declared
+/// by the renderer (`xml`, `xmlns` or `xlink`)
+fn is_xml_attr_name(name: &str) -> bool {
+    let local = match name.split_once(':') {
+        Some(("xml" | "xmlns" | "xlink", local)) => local,
+        Some(_) => return false,
+        None => name,
+    };
+    let mut chars = local.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Writes the namespace declarations missing in `html` and `svg` elements, for XML
+fn write_xml_namespaces<E, W>(el: &E, w: &mut W) -> fmt::Result
+where
+    E: Element,
+    W: fmt::Write + ?Sized,
+{
+    let attrs = el.attrs();
+    let namespace = match el.tag() {
+        "html" => XHTML_NAMESPACE,
+        "svg" => SVG_NAMESPACE,
+        _ => return Ok(()),
+    };
+    if !attrs.contains_key("xmlns") {
+        write!(w, " xmlns=\"{namespace}\"")?;
+    }
+    if namespace == SVG_NAMESPACE && !attrs.contains_key("xmlns:xlink") {
+        let mut found = has_xlink_attr(el);
+        walk_nodes(el.children(), |o| match o {
+            HtmlNode::SvgElement(x) if has_xlink_attr(x) => {
+                found = true;
+                Walk::Stop
             }
-            buf.pop();
-            buf.push('"');
+            _ => Walk::Continue,
+        });
+        if found {
+            write!(w, " xmlns:xlink=\"{XLINK_NAMESPACE}\"")?;
         }
+    }
+    Ok(())
+}
 
-        for (k, v) in self.attrs() {
-            buf.push(' ');
-            buf.push_str(k);
-            v.render_to(buf);
-        }
+/// Writes the opening tag of `el` and schedules its children and closing tag
+fn open_element<'a, E, W>(
+    el: &'a E,
+    w: &mut W,
+    indent: usize,
+    flow: Flow<'a>,
+    opts: &RenderOptions,
+    stack: &mut Vec<Task<'a>>,
+) -> fmt::Result
+where
+    E: Element,
+    W: fmt::Write + ?Sized,
+{
+    let tag = el.tag();
+    let xml = opts.syntax == Syntax::Xml;
 
-        if self.is_void_tag() {
-            buf.push_str(" />");
-            return;
-        }
+    if flow == Flow::Indented {
+        opts.write_indent(w, indent)?;
+    }
+    w.write_char('<')?;
+    w.write_str(tag)?;
 
-        if self.children().is_empty() {
-            buf.push_str("></");
-            buf.push_str(tag);
-            buf.push('>');
-            return;
+    if !el.classes().is_empty() {
+        w.write_str(" class=\"")?;
+        for (i, o) in el.classes().iter().enumerate() {
+            if i > 0 {
+                w.write_char(' ')?;
+            }
+            write_escaped_html(o, w)?;
         }
+        w.write_char('"')?;
+    }
 
-        if self.has_inline_content() {
-            buf.push('>');
-            self.children().iter().for_each(|c| c.render_to(buf, 0));
-            buf.push_str("</");
-            buf.push_str(tag);
-            buf.push('>');
+    let styles = el.styles().filter(|o| !o.is_empty());
+    for (k, v) in el.attrs() {
+        if xml && !is_xml_attr_name(k) {
+            continue;
+        }
+        w.write_char(' ')?;
+        w.write_str(k)?;
+        if let Some(styles) = styles
+            && k == "style"
+        {
+            let style = AttributeValue::Value(merge_style(Some(v), styles));
+            write_attr_value(&style, w, opts)?;
         } else {
-            buf.push_str(">\n");
-            for c in self.children() {
-                c.render_to(buf, indent + 1);
-                buf.push('\n');
-            }
-            buf.push_str(&pad);
-            buf.push_str("</");
-            buf.push_str(tag);
-            buf.push('>');
+            write_attr_value(v, w, opts)?;
         }
     }
+    if let Some(styles) = styles
+        && !el.attrs().contains_key("style")
+    {
+        w.write_str(" style")?;
+        write_attr_value(&AttributeValue::Value(merge_style(None, styles)), w, opts)?;
+    }
+    if xml {
+        write_xml_namespaces(el, w)?;
+    }
 
-    fn size_hint(&self) -> usize {
-        let tag_len = self.tag().len() * 2 + 5;
-        let attrs_len = self
-            .attrs()
-            .iter()
-            .map(|(k, v)| k.len() + v.size_hint())
-            .sum::<usize>();
-        let children_len = self.children().iter().map(|c| c.size_hint()).sum::<usize>();
-        tag_len + attrs_len + children_len
+    if el.is_void_tag() || (xml && el.children().is_empty()) {
+        return w.write_str(" />");
     }
-}
 
-#[inline]
-fn push_str_with_indent(s: &str, buf: &mut String, indent: usize) {
-    if indent == 0 {
-        buf.push_str(s);
+    if el.children().is_empty() {
+        w.write_str("></")?;
+        w.write_str(tag)?;
+        return w.write_char('>');
+    }
+
+    w.write_char('>')?;
+    if el.is_raw_text_tag() {
+        stack.push(Task::Close(tag, None));
+        let flow = if xml {
+            w.write_str("<![CDATA[")?;
+            stack.push(Task::Str("]]>"));
+            Flow::CData
+        } else {
+            Flow::RawText(tag)
+        };
+        stack.extend(el.children().iter().rev().map(|c| Task::Node(c, 0, flow)));
+    } else if flow != Flow::Indented || preserves_whitespace(el) {
+        // A leading newline would be dropped by the browser, unless there is another one (XML
+        // parsers keep it)
+        let leading_newline =
+            matches!(el.children().first(), Some(HtmlNode::Text(s)) if s.starts_with('\n'));
+        if leading_newline && !xml && PREFORMATTED_ELEMENTS.contains(&tag) {
+            w.write_char('\n')?;
+        }
+        stack.push(Task::Close(tag, None));
+        let flow = match flow {
+            Flow::RawText(_) | Flow::CData => flow,
+            _ => Flow::Verbatim,
+        };
+        stack.extend(el.children().iter().rev().map(|c| Task::Node(c, 0, flow)));
+    } else if el.has_inline_content() {
+        stack.push(Task::Close(tag, None));
+        stack.extend(
+            el.children()
+                .iter()
+                .rev()
+                .map(|c| Task::Node(c, 0, Flow::Indented)),
+        );
     } else {
-        let pad = "  ".repeat(indent);
-        s.lines().for_each(|o| {
-            buf.push_str(&pad);
-            buf.push_str(o);
-            buf.push('\n');
-        });
-        buf.pop();
+        opts.write_newline(w)?;
+        stack.push(Task::Close(tag, Some(indent)));
+        for c in el.children().iter().rev() {
+            stack.push(Task::Newline);
+            stack.push(Task::Node(c, indent + 1, Flow::Indented));
+        }
     }
+    Ok(())
 }
 
-impl Render for HtmlNode {
-    fn render_to(&self, buf: &mut String, indent: usize) {
-        match self {
-            HtmlNode::Doctype => push_str_with_indent("<!DOCTYPE html>", buf, indent),
-            HtmlNode::Raw(s) => push_str_with_indent(s, buf, indent),
-            HtmlNode::Text(s) => escape_html_to_with_indent(s, buf, indent),
-            HtmlNode::Element(el) => el.render_to(buf, indent),
-            HtmlNode::SvgElement(el) => el.render_to(buf, indent),
-            HtmlNode::Fragment(nodes) => {
-                for node in nodes {
-                    node.render_to(buf, indent);
-                    buf.push('\n');
+/// Node to render, pre-rendered nodes with other options are rendered again from their node
+fn live_node<'a>(mut node: &'a HtmlNode, opts: &RenderOptions) -> &'a HtmlNode {
+    while let HtmlNode::Prerendered(x) = node {
+        match x.live_node(opts) {
+            Some(x) => node = x,
+            None => break,
+        }
+    }
+    node
+}
+
+/// Processes the pending tasks until the stack is empty
+fn run_tasks<'a, W: fmt::Write + ?Sized>(
+    stack: &mut Vec<Task<'a>>,
+    w: &mut W,
+    opts: &RenderOptions,
+) -> fmt::Result {
+    while let Some(task) = stack.pop() {
+        match task {
+            Task::Node(node, indent, flow) => match (live_node(node, opts), flow) {
+                (HtmlNode::Raw(s) | HtmlNode::Text(s), Flow::RawText(tag)) => {
+                    write_raw_text(s, tag, w)?
+                }
+                (HtmlNode::Doctype, _) => {
+                    write_html_lines("<!DOCTYPE html>", w, indent, opts, false)?
+                }
+                (HtmlNode::Prerendered(x), Flow::RawText(tag)) => {
+                    write_raw_text(x.as_str(), tag, w)?
                 }
-                buf.pop();
+                (HtmlNode::Raw(s) | HtmlNode::Text(s), Flow::CData) => write_cdata(s, w)?,
+                (HtmlNode::Prerendered(x), Flow::CData) => write_cdata(x.as_str(), w)?,
+                (HtmlNode::Raw(s), _) => write_html_lines(s, w, indent, opts, false)?,
+                (HtmlNode::Prerendered(x), Flow::Indented) => x.write_indented(w, indent, opts)?,
+                (HtmlNode::Prerendered(x), _) => w.write_str(x.as_str())?,
+                (HtmlNode::Text(s), _) => write_html_lines(s, w, indent, opts, true)?,
+                (HtmlNode::Element(el), _) => open_element(el, w, indent, flow, opts, stack)?,
+                (HtmlNode::SvgElement(el), _) => open_element(el, w, indent, flow, opts, stack)?,
+                (HtmlNode::Fragment(nodes), _) => {
+                    for (i, node) in nodes.iter().enumerate().rev() {
+                        stack.push(Task::Node(node, indent, flow));
+                        if i > 0 && flow == Flow::Indented {
+                            stack.push(Task::Newline);
+                        }
+                    }
+                }
+            },
+            Task::Newline => opts.write_newline(w)?,
+            Task::Str(s) => w.write_str(s)?,
+            Task::Close(tag, indent) => {
+                if let Some(indent) = indent {
+                    opts.write_indent(w, indent)?;
+                }
+                w.write_str("</")?;
+                w.write_str(tag)?;
+                w.write_char('>')?;
             }
         }
     }
+    Ok(())
+}
 
-    fn size_hint(&self) -> usize {
-        match self {
+/// Size hint of an element without its children
+fn element_size_hint<E: Element>(el: &E) -> usize {
+    let tag_len = el.tag().len() * 2 + 5;
+    let attrs_len = el
+        .attrs()
+        .iter()
+        .map(|(k, v)| k.len() + v.size_hint())
+        .sum::<usize>();
+    tag_len + attrs_len
+}
+
+/// Adds up the size hint of the nodes (and all their descendants) in the stack
+fn nodes_size_hint(mut stack: Vec<&HtmlNode>) -> usize {
+    let mut total = 0;
+    while let Some(node) = stack.pop() {
+        total += match node {
             HtmlNode::Doctype => 15,
             HtmlNode::Raw(s) | HtmlNode::Text(s) => s.len(),
-            HtmlNode::Element(el) => el.size_hint(),
-            HtmlNode::SvgElement(el) => el.size_hint(),
-            HtmlNode::Fragment(nodes) => nodes.iter().map(|n| n.size_hint()).sum(),
-        }
+            HtmlNode::Prerendered(x) => x.as_str().len(),
+            HtmlNode::Element(el) => {
+                stack.extend(el.children());
+                element_size_hint(el)
+            }
+            HtmlNode::SvgElement(el) => {
+                stack.extend(el.children());
+                element_size_hint(el)
+            }
+            HtmlNode::Fragment(nodes) => {
+                stack.extend(nodes);
+                0
+            }
+        };
+    }
+    total
+}
+
+impl<T: Element> Render for T {
+    fn write_with<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        indent: usize,
+        opts: &RenderOptions,
+    ) -> fmt::Result {
+        #[cfg(all(debug_assertions, feature = "debug_validate"))]
+        crate::validate::debug_validate(self);
+        let mut stack = Vec::new();
+        open_element(self, w, indent, Flow::Indented, opts, &mut stack)?;
+        run_tasks(&mut stack, w, opts)
+    }
+
+    fn size_hint(&self) -> usize {
+        element_size_hint(self) + nodes_size_hint(self.children().iter().collect())
+    }
+}
+
+impl Render for HtmlNode {
+    fn write_with<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        indent: usize,
+        opts: &RenderOptions,
+    ) -> fmt::Result {
+        #[cfg(all(debug_assertions, feature = "debug_validate"))]
+        crate::validate::debug_validate(self);
+        let mut stack = vec![Task::Node(self, indent, Flow::Indented)];
+        run_tasks(&mut stack, w, opts)
+    }
+
+    fn size_hint(&self) -> usize {
+        nodes_size_hint(vec![self])
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::html_element::*;
+    use crate::{
+        children,
+        components::theme_toogle,
+        html_element::*,
+        node::{IntoNode, fragment},
+        svg::*,
+    };
 
     #[test]
     fn simple_render() {
@@ -200,4 +710,359 @@ mod tests {
         </div>
         ");
     }
+
+    #[test]
+    fn render_fmt_works() {
+        let el = div().class("card").add(p().add("Some <text>"));
+        let mut buf = String::new();
+        el.render_fmt(&mut buf).unwrap();
+        assert_eq!(buf, el.render());
+    }
+
+    #[test]
+    fn render_io_works() {
+        let el = div().class("card").add(p().add("Some <text>"));
+        let mut buf = Vec::new();
+        el.render_io(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), el.render());
+    }
+
+    #[test]
+    fn render_io_keeps_io_error() {
+        struct FailingWriter;
+
+        impl io::Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let err = div().add("text").render_io(&mut FailingWriter).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn render_bytes_works() {
+        let el = div().class("card").add(p().add("Some <text>"));
+        assert_eq!(el.render_bytes(), el.render().as_bytes());
+    }
+
+    fn options_sample() -> HtmlElement {
+        div()
+            .class("card")
+            .add(p().add("A paragraph"))
+            .add(ul().add(li().add("one")).add(li().add("two")))
+            .add("Some\nlines")
+    }
+
+    #[test]
+    fn render_compact_works() {
+        let res = options_sample().render_compact();
+        assert_eq!(
+            res,
+            "<div class=\"card\"><p>A paragraph</p><ul><li>one</li><li>two</li></ul>Some\nlines</div>"
+        );
+    }
+
+    #[test]
+    fn render_with_tabs() {
+        let opts = RenderOptions::pretty().indent(Indent::Tab);
+        let res = options_sample().render_with(&opts);
+        assert_eq!(
+            res,
+            "<div class=\"card\">\n\t<p>A paragraph</p>\n\t<ul>\n\t\t<li>one</li>\n\t\t<li>two</li>\n\t</ul>\n\tSome\n\tlines\n</div>"
+        );
+    }
+
+    #[test]
+    fn render_with_four_spaces() {
+        let opts = RenderOptions::pretty().indent(Indent::Spaces(4));
+        let res = options_sample().render_with(&opts);
+        insta::assert_snapshot!(res, @r#"
+        <div class="card">
+            <p>A paragraph</p>
+            <ul>
+                <li>one</li>
+                <li>two</li>
+            </ul>
+            Some
+            lines
+        </div>
+        "#);
+    }
+
+    #[test]
+    fn render_newlines_without_indent() {
+        let opts = RenderOptions::pretty().indent(Indent::None);
+        let res = options_sample().render_with(&opts);
+        insta::assert_snapshot!(res, @r#"
+        <div class="card">
+        <p>A paragraph</p>
+        <ul>
+        <li>one</li>
+        <li>two</li>
+        </ul>
+        Some
+        lines
+        </div>
+        "#);
+    }
+
+    #[test]
+    fn with_options_works() {
+        let el = options_sample();
+        let mut buf = Vec::new();
+        el.with_options(RenderOptions::compact())
+            .render_io(&mut buf)
+            .unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), el.render_compact());
+    }
+
+    #[test]
+    fn render_deeply_nested_tree() {
+        const DEPTH: usize = 100_000;
+
+        let mut node = span().add("leaf").into_node();
+        for _ in 0..DEPTH {
+            node = span().add(node).into_node();
+        }
+        let res = node.render();
+        let expected = format!(
+            "{}<span>leaf</span>{}",
+            "<span>".repeat(DEPTH),
+            "</span>".repeat(DEPTH)
+        );
+        assert_eq!(res, expected);
+        assert!(node.size_hint() >= expected.len() / 2);
+        assert_eq!(node.clone().render(), expected);
+
+        let mut node = div().add("leaf").into_node();
+        for _ in 0..DEPTH {
+            node = div().add(fragment([node])).into_node();
+        }
+        let res = node.render_compact();
+        let expected = format!(
+            "{}<div>leaf</div>{}",
+            "<div>".repeat(DEPTH),
+            "</div>".repeat(DEPTH)
+        );
+        assert_eq!(res, expected);
+        drop(node);
+
+        let mut el = svg_g();
+        for _ in 0..DEPTH {
+            el = svg_g().add(el);
+        }
+        let res = el.render_compact();
+        let expected = format!("{}{}", "<g>".repeat(DEPTH + 1), "</g>".repeat(DEPTH + 1));
+        assert_eq!(res, expected);
+        assert_eq!(el.clone().render_compact(), expected);
+    }
+
+    #[test]
+    fn render_fragments_and_nested_blocks() {
+        let res = fragment(children![
+            HtmlNode::Doctype,
+            div()
+                .add(p().add(span().add("inline")).add("text"))
+                .add(fragment(["one", "two"]))
+                .add(svg().add(svg_text().add("svg text"))),
+            "Some\ntrailing text",
+        ])
+        .render();
+        insta::assert_snapshot!(res, @r#"
+        <!DOCTYPE html>
+        <div>
+          <p><span>inline</span>text</p>
+          one
+          two
+          <svg xmlns="http://www.w3.org/2000/svg"><text>svg text</text></svg>
+        </div>
+        Some
+        trailing text
+        "#);
+    }
+
+    #[test]
+    fn preformatted_content_is_verbatim() {
+        let src = "fn main() {\n    println!(\"hi\");\n}";
+        let res = div()
+            .add(
+                section().add(
+                    pre()
+                        .add(code().add(src))
+                        .add(div().add(span().add("  block  ")))
+                        .add(fragment(["a", "b"])),
+                ),
+            )
+            .add(textarea().add("line 1\n  line 2"))
+            .render();
+        let expected = [
+            "<div>",
+            "  <section>",
+            "    <pre><code>fn main() {\n    println!(&quot;hi&quot;);\n}</code><div><span>  block  </span></div>ab</pre>",
+            "  </section>",
+            "  <textarea>line 1\n  line 2</textarea>",
+            "</div>",
+        ]
+        .join("\n");
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn whitespace_pre_classes_and_styles() {
+        let res = div()
+            .add(
+                div()
+                    .class("whitespace-pre-wrap")
+                    .add(p().add(" a "))
+                    .add("\n b"),
+            )
+            .add(
+                div()
+                    .style("color: red; white-space: pre-line")
+                    .add(p().add("c")),
+            )
+            .add(div().style("white-space: normal").add(p().add("d")))
+            .add(div().class("md:whitespace-pre").add(p().add("e")))
+            .add(div().class("!whitespace-pre-line").add(p().add("f")))
+            .render();
+        let expected = [
+            "<div>",
+            "  <div class=\"whitespace-pre-wrap\"><p> a </p>\n b</div>",
+            "  <div style=\"color: red; white-space: pre-line\"><p>c</p></div>",
+            "  <div style=\"white-space: normal\">",
+            "    <p>d</p>",
+            "  </div>",
+            "  <div class=\"md:whitespace-pre\"><p>e</p></div>",
+            "  <div class=\"!whitespace-pre-line\"><p>f</p></div>",
+            "</div>",
+        ]
+        .join("\n");
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn preformatted_leading_newline_is_kept() {
+        let res = div()
+            .add(pre().add("\nfirst line"))
+            .add(textarea().add("\n"))
+            .add(div().class("whitespace-pre").add("\nnot doubled"))
+            .render_compact();
+        insta::assert_snapshot!(res, @r#"
+        <div><pre>
+
+        first line</pre><textarea>
+
+        </textarea><div class="whitespace-pre">
+        not doubled</div></div>
+        "#);
+    }
+
+    #[test]
+    fn render_svg_as_xml() {
+        let icon = svg()
+            .view_box("0 0 24 24")
+            .add(svg_defs().add(svg_circle().id("dot").r(4)))
+            .add(svg_use().xlink_href("#dot").x(2))
+            .add(svg_g())
+            .add(svg_text().add("Tom & Jerry"));
+        insta::assert_snapshot!(icon.render_xml(), @r##"
+        <?xml version="1.0" encoding="UTF-8"?>
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" xmlns:xlink="http://www.w3.org/1999/xlink">
+          <defs>
+            <circle id="dot" r="4" />
+          </defs>
+          <use xlink:href="#dot" x="2" />
+          <g />
+          <text>Tom &amp; Jerry</text>
+        </svg>
+        "##);
+    }
+
+    #[test]
+    fn render_xhtml() {
+        let page = fragment(children![
+            HtmlNode::Doctype,
+            html().add(
+                body()
+                    .add(input().name("q").required().hx_get("/search?a=1&b=<2>"))
+                    .add(div())
+                    .add(pre().add("\nfirst line"))
+                    .add(script().add_raw("if (a < b && c) { x = ']]>'; }"))
+                    .add(svg().add(svg_path().d("M0 0"))),
+            ),
+        ]);
+        insta::assert_snapshot!(page.render_with(&RenderOptions::xml()), @r#"
+        <!DOCTYPE html>
+        <html xmlns="http://www.w3.org/1999/xhtml">
+          <body>
+            <input name="q" required="" hx-get="/search?a=1&amp;b=&lt;2&gt;" />
+            <div />
+            <pre>
+        first line</pre>
+            <script><![CDATA[if (a < b && c) { x = ']]]]><![CDATA[>'; }]]></script>
+            <svg xmlns="http://www.w3.org/2000/svg">
+              <path d="M0 0" />
+            </svg>
+          </body>
+        </html>
+        "#);
+    }
+
+    #[test]
+    fn xml_skips_invalid_attribute_names() {
+        let res = div()
+            .set_attr("@click", "open = true")
+            .set_attr(":class", "{ open }")
+            .set_attr("x-on:keyup.enter", "submit()")
+            .set_attr("hx-on:click", "alert(1)")
+            .set_attr("data-x.y", "1")
+            .set_attr("xml:lang", "en")
+            .add(svg().add(svg_use().xlink_href("#dot")))
+            .render_with(&RenderOptions::xml());
+        insta::assert_snapshot!(res, @r##"
+        <div data-x.y="1" xml:lang="en">
+          <svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+            <use xlink:href="#dot" />
+          </svg>
+        </div>
+        "##);
+        assert!(!theme_toogle().render_xml().contains('@'));
+    }
+
+    #[test]
+    fn raw_text_elements_escape_end_tags() {
+        let user_input = "</script><script>alert(1)</script>";
+        let res = div()
+            .add(script().add(format!(
+                "const name = \"{user_input}\";\nif (a < b && c) {{}}"
+            )))
+            .add(script().add_raw("x = '<!-- <script> </SCRIPT >';"))
+            .add(
+                script()
+                    .typ("application/json")
+                    .add(r#"{"a": "<!-- <script></script>"}"#),
+            )
+            .add(style().add("p::after { content: '</style>'; } a > b {}"))
+            .add(svg().add(svg_style().add("a > b {}")))
+            .render();
+        insta::assert_snapshot!(res, @r#"
+        <div>
+          <script>const name = "<\/script><script>alert(1)<\/script>";
+        if (a < b && c) {}</script>
+          <script>x = '\u003c!-- <script> <\/SCRIPT >';</script>
+          <script type="application/json">{"a": "\u003c!-- <script><\/script>"}</script>
+          <style>p::after { content: '<\/style>'; } a > b {}</style>
+          <svg xmlns="http://www.w3.org/2000/svg">
+            <style>a &gt; b {}</style>
+          </svg>
+        </div>
+        "#);
+    }
 }
@@ -0,0 +1,281 @@
+use crate::attribute::{AttributeValue, IntoAttributeValue};
+use std::borrow::Cow;
+
+/// Value used in place of urls with an unsafe scheme
+pub const UNSAFE_URL_REPLACEMENT: &str = "about:invalid";
+
+/// Schemes allowed in url attributes (besides `data:` urls of images, audio and video)
+pub const SAFE_URL_SCHEMES: &[&str] = &["http", "https", "mailto", "tel", "ws", "wss"];
+
+/// Attributes holding urls, their setters (and the `html!` macro) replace the unsafe ones
+pub const URL_ATTRS: &[&str] = &[
+    "action",
+    "cite",
+    "formaction",
+    "href",
+    "poster",
+    "src",
+    "srcset",
+    "xlink:href",
+    "hx-delete",
+    "hx-get",
+    "hx-patch",
+    "hx-post",
+    "hx-put",
+    "sse-connect",
+    "ws-connect",
+];
+
+/// An url that skips the safety checks of the url setters (`href`, `src`, `hx_get`, ...).
+/// Only use it for urls that don't come from user input.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let link = a().href(TrustedUrl::new("javascript:history.back()")).add("Back");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedUrl(pub String);
+
+impl TrustedUrl {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self(url.into())
+    }
+}
+
+/// Values accepted by the url setters. Urls are checked with `is_safe_url` and replaced by
+/// `about:invalid` when unsafe, except for `TrustedUrl`.
+pub trait IntoUrlValue {
+    /// Transforms into the value for the url attribute `attr`
+    fn into_url_attr(self, attr: &str) -> Option<AttributeValue>;
+}
+
+impl<T: IntoAttributeValue> IntoUrlValue for T {
+    fn into_url_attr(self, attr: &str) -> Option<AttributeValue> {
+        self.into_attr().map(|value| match value {
+            AttributeValue::Value(v) => AttributeValue::Value(sanitize_url_attr(attr, v)),
+            AttributeValue::Raw(v) => AttributeValue::Raw(sanitize_url_attr(attr, v)),
+            AttributeValue::Empty => AttributeValue::Empty,
+        })
+    }
+}
+
+impl IntoUrlValue for TrustedUrl {
+    fn into_url_attr(self, _attr: &str) -> Option<AttributeValue> {
+        Some(AttributeValue::Value(self.0))
+    }
+}
+
+/// Checks if the value of the url attribute `attr` is kept by the url setters
+pub(crate) fn is_safe_url_attr(attr: &str, value: &str) -> bool {
+    if attr == "srcset" {
+        matches!(sanitize_srcset(value), Cow::Borrowed(_))
+    } else {
+        is_safe_url(value)
+    }
+}
+
+fn sanitize_url_attr(attr: &str, value: String) -> String {
+    if attr == "srcset" {
+        return sanitize_srcset(&value).into_owned();
+    }
+    if is_safe_url(&value) {
+        value
+    } else {
+        UNSAFE_URL_REPLACEMENT.to_string()
+    }
+}
+
+/// Checks if an url can be used in an attribute without running code when followed: relative
+/// urls, the `SAFE_URL_SCHEMES` and `data:` urls of images (except svg), audio and video.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// assert!(is_safe_url("/users?page=2"));
+/// assert!(is_safe_url("https://example.com"));
+/// assert!(!is_safe_url(" JavaScript:alert(1)"));
+/// assert!(!is_safe_url("data:text/html,<script>alert(1)</script>"));
+/// ```
+pub fn is_safe_url(url: &str) -> bool {
+    match url_scheme(url) {
+        None => true,
+        Some(scheme) if scheme == "data" => is_media_data_url(url),
+        Some(scheme) => SAFE_URL_SCHEMES.contains(&scheme.as_str()),
+    }
+}
+
+/// Gets the lowercase scheme of an url, `None` for relative urls
+pub(crate) fn url_scheme(url: &str) -> Option<String> {
+    // Browsers ignore leading and trailing control characters and spaces, and tabs or newlines
+    // anywhere in the url
+    let url = url.trim_matches(|c: char| c <= ' ');
+    let end = url.find([':', '/', '?', '#'])?;
+    if !url[end..].starts_with(':') {
+        return None;
+    }
+    let scheme = url[..end]
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>();
+    Some(scheme.to_ascii_lowercase())
+}
+
+/// Checks if a `data:` url holds an image (except svg), audio or video
+pub(crate) fn is_media_data_url(url: &str) -> bool {
+    let Some((_, data)) = url.split_once(':') else {
+        return false;
+    };
+    let mime = data
+        .split([';', ','])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    (mime.starts_with("image/") && !mime.starts_with("image/svg"))
+        || mime.starts_with("audio/")
+        || mime.starts_with("video/")
+}
+
+/// Replaces the unsafe urls of a `srcset` value (eg: `a.png 1x, b.png 2x`), keeping the
+/// descriptors
+pub fn sanitize_srcset(srcset: &str) -> Cow<'_, str> {
+    sanitize_srcset_with(srcset, is_safe_url)
+}
+
+/// Replaces the urls of a `srcset` value rejected by `is_safe`
+pub(crate) fn sanitize_srcset_with(srcset: &str, is_safe: impl Fn(&str) -> bool) -> Cow<'_, str> {
+    let mut res = String::new();
+    let mut last = 0;
+    let mut rest = srcset;
+    loop {
+        let start = srcset.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_whitespace() || c == ',')
+                .len();
+        if start == srcset.len() {
+            break;
+        }
+        let url_len = srcset[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(srcset.len() - start);
+        let url = srcset[start..start + url_len].trim_end_matches(',');
+        if !is_safe(url) {
+            res.push_str(&srcset[last..start]);
+            res.push_str(UNSAFE_URL_REPLACEMENT);
+            last = start + url.len();
+        }
+        // Descriptors go until the next comma, unless the url already ended with one
+        let after = start + url.len();
+        rest = if url.len() < url_len {
+            &srcset[after..]
+        } else {
+            match srcset[after..].find(',') {
+                Some(i) => &srcset[after + i..],
+                None => "",
+            }
+        };
+    }
+    if last == 0 {
+        return Cow::Borrowed(srcset);
+    }
+    res.push_str(&srcset[last..]);
+    Cow::Owned(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{element::Element, html_element::*, render::Render, svg::*};
+
+    #[test]
+    fn is_safe_url_works() {
+        for url in [
+            "",
+            "/a/b?c=d#e",
+            "page.html",
+            "//cdn.example.com/x.js",
+            "HTTPS://example.com",
+            "mailto:me@example.com",
+            "tel:+123",
+            "wss://example.com/ws",
+            "?q=a:b",
+            "#section:1",
+            "data:image/png;base64,iVBORw0KGgo=",
+            "data:video/mp4,xyz",
+        ] {
+            assert!(is_safe_url(url), "{url}");
+        }
+        for url in [
+            "javascript:alert(1)",
+            "  JaVaScRiPt:alert(1)",
+            "java\tscript:alert(1)",
+            "\u{1}javascript:alert(1)",
+            "vbscript:msgbox",
+            "data:text/html,<script>alert(1)</script>",
+            "data:image/svg+xml;base64,PHN2Zz4=",
+            "data:,hi",
+            "file:///etc/passwd",
+        ] {
+            assert!(!is_safe_url(url), "{url}");
+        }
+    }
+
+    #[test]
+    fn sanitize_srcset_works() {
+        let safe = "a.png 1x, /b.png 2x,data:image/png;base64,AA== 3x";
+        assert!(matches!(sanitize_srcset(safe), Cow::Borrowed(_)));
+        insta::assert_snapshot!(
+            sanitize_srcset("a.png 480w, javascript:alert(1) 800w,data:text/html,x 2x, b.png"),
+            @"a.png 480w, about:invalid 800w,about:invalid 2x, b.png"
+        );
+    }
+
+    #[test]
+    fn url_setters_are_safe() {
+        let user_input = "javascript:alert(document.cookie)";
+        let res = div()
+            .add(a().href(user_input).add("link"))
+            .add(
+                img()
+                    .src(" data:text/html,x")
+                    .srcset("ok.png 1x, javascript:x 2x"),
+            )
+            .add(
+                form()
+                    .action(user_input)
+                    .add(button().formaction(user_input)),
+            )
+            .add(button().hx_get(user_input).hx_post("/todo?a=1&b=2"))
+            .add(
+                svg()
+                    .add(svg_a().href(user_input))
+                    .add(svg_use().xlink_href(user_input)),
+            )
+            .render();
+        insta::assert_snapshot!(res, @r##"
+        <div>
+          <a href="about:invalid">link</a>
+          <img src="about:invalid" srcset="ok.png 1x, about:invalid 2x" />
+          <form action="about:invalid"><button formaction="about:invalid"></button></form>
+          <button hx-get="about:invalid" hx-post="/todo?a=1&amp;b=2"></button>
+          <svg xmlns="http://www.w3.org/2000/svg">
+            <a href="about:invalid"></a>
+            <use xlink:href="about:invalid"></use>
+          </svg>
+        </div>
+        "##);
+    }
+
+    #[test]
+    fn trusted_url_skips_checks() {
+        let res = a()
+            .href(TrustedUrl::new("javascript:history.back()"))
+            .set_url_attr("ping", "/track")
+            .set_url_attr("data-url", "vbscript:x")
+            .render();
+        insta::assert_snapshot!(res, @r#"<a href="javascript:history.back()" ping="/track" data-url="about:invalid"></a>"#);
+    }
+}
@@ -0,0 +1,462 @@
+use crate::{
+    attribute::AttributeValue,
+    element::Element,
+    node::HtmlNode,
+    parse::parse_skipping_unknown_tags,
+    render::Render,
+    safe_url::{is_media_data_url, sanitize_srcset_with, url_scheme},
+    utils::escape_html,
+    walk::Walk,
+};
+use indexmap::{IndexMap, IndexSet};
+use std::borrow::Cow;
+
+type Names = IndexSet<Cow<'static, str>>;
+
+/// Attributes holding urls, checked against the allowed url schemes
+const URL_ATTRS: &[&str] = &[
+    "action",
+    "cite",
+    "formaction",
+    "href",
+    "poster",
+    "src",
+    "srcset",
+    "xlink:href",
+];
+
+fn names<T: Into<Cow<'static, str>>>(names: impl IntoIterator<Item = T>) -> Names {
+    names.into_iter().map(Into::into).collect()
+}
+
+/// Allowlist of tags, attributes and url schemes used to clean untrusted html.
+///
+/// Elements that are not allowed are removed keeping their content, except for the ones in
+/// `drop_tags` (eg: `script`) that are removed with their content. Attributes need to be allowed
+/// for every tag (`allow_attrs`) or for a given tag (`allow_tag_attrs`), and attributes holding
+/// urls are removed when their scheme is not allowed. Comments and doctypes are always removed.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let policy = SanitizePolicy::default()
+///     .allow_tags(["section"])
+///     .allow_tag_attrs("span", ["class"]);
+/// let res = policy.clean_html(r#"<section><span class="x" onclick="alert(1)">Hi</span></section>"#);
+/// assert_eq!(res, r#"<section><span class="x">Hi</span></section>"#);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SanitizePolicy {
+    tags: Names,
+    drop_tags: Names,
+    attrs: Names,
+    tag_attrs: IndexMap<Cow<'static, str>, Names>,
+    url_schemes: Names,
+}
+
+impl Default for SanitizePolicy {
+    /// Policy for user written content (eg: markdown): text formatting, lists, tables, links and
+    /// images without classes, ids or styles.
+    fn default() -> Self {
+        Self::new()
+            .allow_tags([
+                "a",
+                "abbr",
+                "b",
+                "blockquote",
+                "br",
+                "caption",
+                "cite",
+                "code",
+                "dd",
+                "del",
+                "details",
+                "dfn",
+                "div",
+                "dl",
+                "dt",
+                "em",
+                "figcaption",
+                "figure",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "i",
+                "img",
+                "input",
+                "ins",
+                "kbd",
+                "li",
+                "mark",
+                "ol",
+                "p",
+                "pre",
+                "q",
+                "s",
+                "samp",
+                "small",
+                "span",
+                "strong",
+                "sub",
+                "summary",
+                "sup",
+                "table",
+                "tbody",
+                "td",
+                "tfoot",
+                "th",
+                "thead",
+                "time",
+                "tr",
+                "u",
+                "ul",
+                "var",
+                "wbr",
+            ])
+            .allow_attrs(["dir", "lang", "title"])
+            .allow_tag_attrs("a", ["href"])
+            .allow_tag_attrs("blockquote", ["cite"])
+            .allow_tag_attrs("del", ["cite", "datetime"])
+            .allow_tag_attrs("img", ["alt", "height", "src", "srcset", "width"])
+            .allow_tag_attrs("input", ["checked", "disabled", "type"])
+            .allow_tag_attrs("ins", ["cite", "datetime"])
+            .allow_tag_attrs("ol", ["start", "reversed"])
+            .allow_tag_attrs("q", ["cite"])
+            .allow_tag_attrs("td", ["colspan", "rowspan"])
+            .allow_tag_attrs("th", ["colspan", "rowspan", "scope"])
+            .allow_tag_attrs("time", ["datetime"])
+            .allow_url_schemes(["http", "https", "mailto"])
+    }
+}
+
+impl SanitizePolicy {
+    /// Creates a policy that only keeps text. Tags that are never safe to unwrap (`script`,
+    /// `style`, `iframe`, ...) are dropped with their content.
+    pub fn new() -> Self {
+        Self {
+            tags: Names::new(),
+            drop_tags: names([
+                "embed", "frame", "iframe", "noscript", "object", "script", "select", "style",
+                "svg", "template", "textarea", "title",
+            ]),
+            attrs: Names::new(),
+            tag_attrs: IndexMap::new(),
+            url_schemes: Names::new(),
+        }
+    }
+
+    /// Allows some tags
+    pub fn allow_tags<T: Into<Cow<'static, str>>>(
+        mut self,
+        tags: impl IntoIterator<Item = T>,
+    ) -> Self {
+        for tag in tags {
+            let tag = tag.into();
+            self.drop_tags.shift_remove(&tag);
+            self.tags.insert(tag);
+        }
+        self
+    }
+
+    /// Removes some tags from the allowed ones, they will be replaced by their content
+    pub fn deny_tags<T: Into<Cow<'static, str>>>(
+        mut self,
+        tags: impl IntoIterator<Item = T>,
+    ) -> Self {
+        for tag in tags {
+            self.tags.shift_remove(&tag.into());
+        }
+        self
+    }
+
+    /// Removes some tags together with their content
+    pub fn drop_tags<T: Into<Cow<'static, str>>>(
+        mut self,
+        tags: impl IntoIterator<Item = T>,
+    ) -> Self {
+        for tag in tags {
+            let tag = tag.into();
+            self.tags.shift_remove(&tag);
+            self.drop_tags.insert(tag);
+        }
+        self
+    }
+
+    /// Allows some attributes on every allowed tag
+    pub fn allow_attrs<T: Into<Cow<'static, str>>>(
+        mut self,
+        attrs: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.attrs.extend(attrs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Allows some attributes on a tag
+    pub fn allow_tag_attrs<T: Into<Cow<'static, str>>>(
+        mut self,
+        tag: impl Into<Cow<'static, str>>,
+        attrs: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.tag_attrs
+            .entry(tag.into())
+            .or_default()
+            .extend(attrs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Allows some url schemes (eg: `https`) on the url attributes, relative urls are always
+    /// allowed. `data` only allows images, audio and video.
+    pub fn allow_url_schemes<T: Into<Cow<'static, str>>>(
+        mut self,
+        schemes: impl IntoIterator<Item = T>,
+    ) -> Self {
+        let schemes = schemes
+            .into_iter()
+            .map(|o| o.into().to_ascii_lowercase().into());
+        self.url_schemes.extend(schemes);
+        self
+    }
+
+    /// Checks if an url is allowed
+    pub fn is_allowed_url(&self, url: &str) -> bool {
+        match url_scheme(url) {
+            None => true,
+            Some(scheme) if !self.url_schemes.contains(scheme.as_str()) => false,
+            Some(scheme) => scheme != "data" || is_media_data_url(url),
+        }
+    }
+
+    fn is_allowed_attr(&self, tag: &str, attr: &str) -> bool {
+        self.attrs.contains(attr)
+            || self
+                .tag_attrs
+                .get(tag)
+                .is_some_and(|attrs| attrs.contains(attr))
+    }
+
+    /// Cleans a tree of nodes. `HtmlNode::Raw` nodes are parsed and cleaned too.
+    pub fn clean(&self, mut node: HtmlNode) -> HtmlNode {
+        node.walk_mut(|node| self.clean_node(node));
+        // Unwrapped elements are left as fragments
+        node.walk_mut(|node| {
+            match node {
+                HtmlNode::Element(x) => flatten_fragments(x.children_mut()),
+                HtmlNode::SvgElement(x) => flatten_fragments(x.children_mut()),
+                HtmlNode::Fragment(x) => flatten_fragments(x),
+                _ => {}
+            }
+            Walk::Continue
+        });
+        node
+    }
+
+    /// Cleans a html string. Html that can't be parsed (eg: an unfinished tag) is escaped as
+    /// text.
+    pub fn clean_html(&self, html: &str) -> String {
+        match parse_skipping_unknown_tags(html) {
+            Ok(nodes) => self.clean(HtmlNode::Fragment(nodes)).render_compact(),
+            Err(_) => escape_html(html),
+        }
+    }
+
+    fn clean_node(&self, node: &mut HtmlNode) -> Walk {
+        let action = match node {
+            HtmlNode::Doctype => Action::Drop,
+            // Comments, CDATA and other declarations
+            HtmlNode::Raw(x) if x.starts_with("<!") || x.starts_with("<?") => Action::Drop,
+            HtmlNode::Raw(x) => {
+                *node = match parse_skipping_unknown_tags(x) {
+                    Ok(nodes) => HtmlNode::Fragment(nodes),
+                    Err(_) => HtmlNode::Text(std::mem::take(x)),
+                };
+                Action::Keep
+            }
+            HtmlNode::Prerendered(x) => {
+                *node = match parse_skipping_unknown_tags(x.as_str()) {
+                    Ok(nodes) => HtmlNode::Fragment(nodes),
+                    Err(_) => HtmlNode::Text(x.as_str().to_string()),
+                };
+                Action::Keep
+            }
+            HtmlNode::Text(_) | HtmlNode::Fragment(_) => Action::Keep,
+            HtmlNode::Element(x) => self.clean_element(x),
+            HtmlNode::SvgElement(x) => self.clean_element(x),
+        };
+        match action {
+            Action::Keep => Walk::Continue,
+            Action::Unwrap => {
+                let children = match node {
+                    HtmlNode::Element(x) => std::mem::take(x.children_mut()),
+                    HtmlNode::SvgElement(x) => std::mem::take(x.children_mut()),
+                    _ => Vec::new(),
+                };
+                *node = HtmlNode::Fragment(children);
+                Walk::Continue
+            }
+            Action::Drop => {
+                *node = HtmlNode::Fragment(Vec::new());
+                Walk::SkipChildren
+            }
+        }
+    }
+
+    fn clean_element<E: Element>(&self, el: &mut E) -> Action {
+        let tag = el.tag().to_string();
+        let tag = tag.as_str();
+        if self.drop_tags.contains(tag) {
+            return Action::Drop;
+        }
+        if !self.tags.contains(tag) {
+            return Action::Unwrap;
+        }
+        el.attrs_mut().retain(|k, v| {
+            if !self.is_allowed_attr(tag, k) {
+                return false;
+            }
+            let (AttributeValue::Value(url) | AttributeValue::Raw(url)) = v else {
+                return true;
+            };
+            match k.as_ref() {
+                "srcset" => {
+                    *url = sanitize_srcset_with(url, |o| self.is_allowed_url(o)).into_owned();
+                    true
+                }
+                k if URL_ATTRS.contains(&k) => self.is_allowed_url(url),
+                _ => true,
+            }
+        });
+        if !self.is_allowed_attr(tag, "class") {
+            el.classes_mut().clear();
+        }
+        if !self.is_allowed_attr(tag, "style")
+            && let Some(styles) = el.styles_mut()
+        {
+            styles.clear();
+        }
+        Action::Keep
+    }
+}
+
+/// What to do with a node when cleaning it
+enum Action {
+    Keep,
+    /// Replace the element by its children
+    Unwrap,
+    /// Remove the node with its children
+    Drop,
+}
+
+/// Replaces the fragments in `nodes` by their content
+fn flatten_fragments(nodes: &mut Vec<HtmlNode>) {
+    if !nodes.iter().any(|o| matches!(o, HtmlNode::Fragment(_))) {
+        return;
+    }
+    let mut res = Vec::with_capacity(nodes.len());
+    let mut stack = vec![std::mem::take(nodes).into_iter()];
+    while let Some(iter) = stack.last_mut() {
+        match iter.next() {
+            Some(HtmlNode::Fragment(x)) => stack.push(x.into_iter()),
+            Some(node) => res.push(node),
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    *nodes = res;
+}
+
+/// Cleans untrusted html with the default `SanitizePolicy`
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let res = sanitize_html(r#"<p onclick="steal()">Hi <script>alert(1)</script><a href="javascript:x">there</a></p>"#);
+/// assert_eq!(res, "<p>Hi <a>there</a></p>");
+/// ```
+pub fn sanitize_html(html: &str) -> String {
+    SanitizePolicy::default().clean_html(html)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{html_element::*, node::IntoNode, svg::*};
+
+    #[test]
+    fn sanitize_html_works() {
+        let res = sanitize_html(
+            r#"<!-- hi --><div class="fixed inset-0" style="x" onmouseover="alert(1)">
+<h1 id="x">Title</h1><p>Some <b>bold</b> and <blink>unknown</blink> <u>text</u>.</p>
+<img src="/a.png" srcset="javascript:x 2x, b.png 3x" alt="A" onerror="alert(1)">
+<a href="https://example.com" target="_blank">ok</a> <a href=" JAVASCRIPT:alert(1)">bad</a>
+<iframe src="https://evil.com"><p>gone</p></iframe><svg><script>alert(1)</script></svg>
+<form action="/x"><input type="checkbox" checked name="n"><button>Go</button></form>
+<style>body { display: none }</style><script>alert(1)</script>
+</div>"#,
+        );
+        insta::assert_snapshot!(res, @r#"<div><h1>Title</h1><p>Some <b>bold</b> and unknown <u>text</u>.</p><img src="/a.png" srcset="about:invalid 2x, b.png 3x" alt="A" /> <a href="https://example.com">ok</a> <a>bad</a><input type="checkbox" checked />Go</div>"#);
+    }
+
+    #[test]
+    fn sanitize_keeps_text_escaped() {
+        let res = sanitize_html(
+            r#"<p title="a &quot;b&quot; <c>">1 &lt; 2 &amp;&amp; <i>x</i></p><pre>  a
+  b</pre>"#,
+        );
+        insta::assert_snapshot!(res, @r#"
+        <p title="a &quot;b&quot; &lt;c&gt;">1 &lt; 2 &amp;&amp; <i>x</i></p><pre>  a
+          b</pre>
+        "#);
+        insta::assert_snapshot!(sanitize_html("<p>unfinished <a href='x"), @"&lt;p&gt;unfinished &lt;a href=&#x27;x");
+    }
+
+    #[test]
+    fn sanitize_nodes() {
+        let node = div()
+            .class("card")
+            .add(p().add("Hi ").add(span().hx_get("/x").add("there")))
+            .add_raw("<em onclick='x()'>raw</em><script>alert(1)</script>")
+            .add(svg().add(svg_circle()))
+            .add(fragment_with_doctype())
+            .into_node();
+        let res = SanitizePolicy::default().clean(node).render();
+        insta::assert_snapshot!(res, @r"
+        <div>
+          <p>Hi <span>there</span></p>
+          <em>raw</em>
+        </div>
+        ");
+    }
+
+    fn fragment_with_doctype() -> HtmlNode {
+        HtmlNode::Fragment(vec![HtmlNode::Doctype, HtmlNode::Raw("<!-- x -->".into())])
+    }
+
+    #[test]
+    fn custom_policies() {
+        let html = r#"<section class="a"><p>Hi <a href="ftp://x/y" rel="x">ftp</a></p><script>x</script><img src="data:image/png;base64,AA=="></section>"#;
+        let text_only = SanitizePolicy::new();
+        insta::assert_snapshot!(text_only.clean_html(html), @"Hi ftp");
+
+        let policy = SanitizePolicy::default()
+            .allow_tags(["section"])
+            .deny_tags(["p"])
+            .allow_attrs(["class"])
+            .allow_tag_attrs("a", ["rel"])
+            .allow_url_schemes(["FTP", "data"]);
+        insta::assert_snapshot!(policy.clean_html(html), @r#"<section class="a">Hi <a href="ftp://x/y" rel="x">ftp</a><img src="data:image/png;base64,AA==" /></section>"#);
+
+        let policy = SanitizePolicy::default().drop_tags(["a"]);
+        insta::assert_snapshot!(policy.clean_html(html), @"<p>Hi </p><img />");
+        assert!(!policy.is_allowed_url("data:image/png;base64,AA=="));
+
+        let policy = SanitizePolicy::new().allow_tags(["script"]);
+        insta::assert_snapshot!(policy.clean_html("<script>x</script>"), @"<script>x</script>");
+    }
+}
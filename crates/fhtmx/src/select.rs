@@ -0,0 +1,784 @@
+use crate::{attribute::AttributeValue, element::Element, node::HtmlNode, style::style_attr_value};
+use indexmap::{IndexMap, IndexSet};
+use std::{borrow::Cow, fmt, str::FromStr};
+
+/// Error returned when parsing an invalid css selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorError {
+    pub message: String,
+    /// Byte offset in the selector where the error was found
+    pub position: usize,
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+/// A parsed css selector.
+///
+/// Supports type (`div`, `*`), class (`.card`), id (`#main`) and attribute selectors (`[hidden]`,
+/// `[type=text]`, `[class~=btn]`, `[href^=https]`, `[href$=".pdf"]`, `[href*=docs]`,
+/// `[lang|=en]`), the descendant (` `) and child (`>`) combinators and selector lists (`h1, h2`).
+/// Special characters in names can be escaped with a backslash (eg: `.md\:flex`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    alternatives: Vec<ComplexSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ComplexSelector {
+    compounds: Vec<Compound>,
+    /// `combinators[i]` joins `compounds[i]` and `compounds[i + 1]`
+    combinators: Vec<Combinator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Compound {
+    tag: Option<String>,
+    ids: Vec<String>,
+    classes: Vec<String>,
+    attrs: Vec<AttrSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AttrSelector {
+    name: String,
+    op: Option<(AttrOp, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttrOp {
+    /// `[attr=value]`
+    Equals,
+    /// `[attr~=value]`
+    Includes,
+    /// `[attr|=value]`
+    DashMatch,
+    /// `[attr^=value]`
+    Prefix,
+    /// `[attr$=value]`
+    Suffix,
+    /// `[attr*=value]`
+    Substring,
+}
+
+impl Selector {
+    /// Parses a css selector
+    pub fn parse(selector: &str) -> Result<Self, SelectorError> {
+        SelectorParser {
+            src: selector,
+            pos: 0,
+        }
+        .run()
+    }
+
+    /// Checks if the last element of `chain` (with its ancestors before it) matches
+    fn matches(&self, chain: &[ElementRef<'_>]) -> bool {
+        self.alternatives.iter().any(|o| o.matches(chain))
+    }
+}
+
+impl FromStr for Selector {
+    type Err = SelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl ComplexSelector {
+    fn matches(&self, chain: &[ElementRef<'_>]) -> bool {
+        !chain.is_empty() && self.matches_at(self.compounds.len() - 1, chain, chain.len() - 1)
+    }
+
+    /// Matches `compounds[..=idx]` with `chain[pos]` being the element for `compounds[idx]`
+    fn matches_at(&self, idx: usize, chain: &[ElementRef<'_>], pos: usize) -> bool {
+        if !self.compounds[idx].matches(&chain[pos]) {
+            return false;
+        }
+        if idx == 0 {
+            return true;
+        }
+        match self.combinators[idx - 1] {
+            Combinator::Child => pos > 0 && self.matches_at(idx - 1, chain, pos - 1),
+            Combinator::Descendant => (0..pos).rev().any(|p| self.matches_at(idx - 1, chain, p)),
+        }
+    }
+}
+
+impl Compound {
+    fn matches(&self, el: &ElementRef<'_>) -> bool {
+        if let Some(tag) = &self.tag
+            && !tag.eq_ignore_ascii_case(el.tag)
+        {
+            return false;
+        }
+        self.ids
+            .iter()
+            .all(|id| el.attr_value("id").is_some_and(|o| o == *id))
+            && self.classes.iter().all(|c| el.classes.contains(c.as_str()))
+            && self.attrs.iter().all(|a| a.matches(el))
+    }
+}
+
+impl AttrSelector {
+    fn matches(&self, el: &ElementRef<'_>) -> bool {
+        let Some(value) = el.attr_value(&self.name) else {
+            return false;
+        };
+        let Some((op, expected)) = &self.op else {
+            return true;
+        };
+        let expected = expected.as_str();
+        match op {
+            AttrOp::Equals => value == expected,
+            AttrOp::Includes => value.split_whitespace().any(|o| o == expected),
+            AttrOp::DashMatch => {
+                value == expected
+                    || value
+                        .strip_prefix(expected)
+                        .is_some_and(|o| o.starts_with('-'))
+            }
+            AttrOp::Prefix => !expected.is_empty() && value.starts_with(expected),
+            AttrOp::Suffix => !expected.is_empty() && value.ends_with(expected),
+            AttrOp::Substring => !expected.is_empty() && value.contains(expected),
+        }
+    }
+}
+
+/// Borrowed view of the parts of an element used for matching
+pub(crate) struct ElementRef<'a> {
+    tag: &'a str,
+    attrs: &'a IndexMap<Cow<'static, str>, AttributeValue>,
+    classes: &'a IndexSet<Cow<'static, str>>,
+    styles: Option<&'a IndexMap<Cow<'static, str>, String>>,
+}
+
+impl<'a> ElementRef<'a> {
+    pub(crate) fn new<T: Element>(el: &'a T) -> Self {
+        Self {
+            tag: el.tag(),
+            attrs: el.attrs(),
+            classes: el.classes(),
+            styles: el.styles(),
+        }
+    }
+
+    fn from_node(node: &'a HtmlNode) -> Option<Self> {
+        match node {
+            HtmlNode::Element(x) => Some(Self::new(x)),
+            HtmlNode::SvgElement(x) => Some(Self::new(x)),
+            _ => None,
+        }
+    }
+
+    /// Value of an attribute, `class` being the space separated classes and `style` including
+    /// the style properties
+    fn attr_value(&self, name: &str) -> Option<Cow<'a, str>> {
+        if name.eq_ignore_ascii_case("class") {
+            if self.classes.is_empty() {
+                return None;
+            }
+            let classes = self.classes.iter().map(|o| o.as_ref());
+            return Some(classes.collect::<Vec<_>>().join(" ").into());
+        }
+        let value = self
+            .attrs
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v);
+        if name.eq_ignore_ascii_case("style") {
+            return style_attr_value(value, self.styles);
+        }
+        value.map(|v| match v {
+            AttributeValue::Empty => Cow::Borrowed(""),
+            AttributeValue::Value(x) | AttributeValue::Raw(x) => Cow::Borrowed(x.as_str()),
+        })
+    }
+}
+
+/// Visits the nodes in document order, calling `f` with the path (child indexes starting from
+/// `nodes`) of every element accepted by `pred`. `pred` receives the element with its ancestors
+/// before it, starting with `root` when given. Stops when `f` returns false.
+fn find_nodes<'a, P, F>(root: Option<ElementRef<'a>>, nodes: &'a [HtmlNode], mut pred: P, mut f: F)
+where
+    P: FnMut(&[ElementRef<'a>]) -> bool,
+    F: FnMut(&[usize], &'a HtmlNode) -> bool,
+{
+    let base = usize::from(root.is_some());
+    let mut chain = Vec::from_iter(root);
+    let mut path = Vec::new();
+    // (node, index in its parent, nesting level, number of element ancestors)
+    let mut stack = nodes
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, o)| (o, i, 0, base))
+        .collect::<Vec<_>>();
+    while let Some((node, index, level, depth)) = stack.pop() {
+        path.truncate(level);
+        path.push(index);
+        let (children, depth) = match node {
+            HtmlNode::Element(x) => (x.children(), depth + 1),
+            HtmlNode::SvgElement(x) => (x.children(), depth + 1),
+            HtmlNode::Fragment(x) => (x.as_slice(), depth),
+            _ => continue,
+        };
+        if let Some(el) = ElementRef::from_node(node) {
+            chain.truncate(depth - 1);
+            chain.push(el);
+            if pred(&chain) && !f(&path, node) {
+                return;
+            }
+        }
+        let items = children.iter().enumerate().rev();
+        stack.extend(items.map(|(i, o)| (o, i, level + 1, depth)));
+    }
+}
+
+/// Follows a path from `find_nodes`
+fn node_at_mut<'a>(nodes: &'a mut [HtmlNode], path: &[usize]) -> Option<&'a mut HtmlNode> {
+    let (first, rest) = path.split_first()?;
+    let mut node = nodes.get_mut(*first)?;
+    for &i in rest {
+        node = match node {
+            HtmlNode::Element(x) => x.children_mut().get_mut(i)?,
+            HtmlNode::SvgElement(x) => x.children_mut().get_mut(i)?,
+            HtmlNode::Fragment(x) => x.get_mut(i)?,
+            _ => return None,
+        };
+    }
+    Some(node)
+}
+
+fn parse_selector(selector: &str) -> Selector {
+    Selector::parse(selector).unwrap_or_else(|e| panic!("invalid selector `{selector}`: {e}"))
+}
+
+fn is_id(id: &str) -> impl FnMut(&[ElementRef<'_>]) -> bool {
+    move |chain| {
+        chain
+            .last()
+            .and_then(|o| o.attr_value("id"))
+            .is_some_and(|o| o == id)
+    }
+}
+
+pub(crate) fn select<'a>(
+    root: Option<ElementRef<'a>>,
+    nodes: &'a [HtmlNode],
+    selector: &str,
+) -> Vec<&'a HtmlNode> {
+    let selector = parse_selector(selector);
+    let mut res = Vec::new();
+    find_nodes(
+        root,
+        nodes,
+        |chain| selector.matches(chain),
+        |_, node| {
+            res.push(node);
+            true
+        },
+    );
+    res
+}
+
+pub(crate) fn select_first<'a>(
+    root: Option<ElementRef<'a>>,
+    nodes: &'a [HtmlNode],
+    selector: &str,
+) -> Option<&'a HtmlNode> {
+    let selector = parse_selector(selector);
+    let mut res = None;
+    find_nodes(
+        root,
+        nodes,
+        |chain| selector.matches(chain),
+        |_, node| {
+            res = Some(node);
+            false
+        },
+    );
+    res
+}
+
+/// Paths of the matching nodes, in document order
+fn select_paths(
+    root: Option<ElementRef<'_>>,
+    nodes: &[HtmlNode],
+    pred: impl FnMut(&[ElementRef<'_>]) -> bool,
+    first_only: bool,
+) -> Vec<Vec<usize>> {
+    let mut res = Vec::new();
+    find_nodes(root, nodes, pred, |path, _| {
+        res.push(path.to_vec());
+        !first_only
+    });
+    res
+}
+
+/// Paths of the nodes matching `selector`, in document order
+pub(crate) fn select_paths_for(
+    root: Option<ElementRef<'_>>,
+    nodes: &[HtmlNode],
+    selector: &str,
+) -> Vec<Vec<usize>> {
+    let selector = parse_selector(selector);
+    select_paths(root, nodes, |chain| selector.matches(chain), false)
+}
+
+/// Calls `f` on the nodes at `paths` (as returned by `select_paths_for`)
+pub(crate) fn update_paths(
+    nodes: &mut [HtmlNode],
+    paths: &[Vec<usize>],
+    mut f: impl FnMut(&mut HtmlNode),
+) {
+    // Going backwards every node is updated after its descendants and later siblings, so changes
+    // made by `f` can't move the nodes still to be visited
+    for path in paths.iter().rev() {
+        if let Some(node) = node_at_mut(nodes, path) {
+            f(node);
+        }
+    }
+}
+
+pub(crate) fn find_by_id<'a>(
+    root: Option<ElementRef<'a>>,
+    nodes: &'a [HtmlNode],
+    id: &str,
+) -> Option<&'a HtmlNode> {
+    let mut res = None;
+    find_nodes(root, nodes, is_id(id), |_, node| {
+        res = Some(node);
+        false
+    });
+    res
+}
+
+pub(crate) fn find_by_id_mut<'a>(nodes: &'a mut [HtmlNode], id: &str) -> Option<&'a mut HtmlNode> {
+    let path = select_paths(None, nodes, is_id(id), true).pop()?;
+    node_at_mut(nodes, &path)
+}
+
+impl HtmlNode {
+    /// Gets all the nodes matching a css selector, in document order, including the node itself.
+    ///
+    /// See `Selector` for the supported syntax.
+    ///
+    /// # Panics
+    ///
+    /// If the selector is invalid, use `Selector::parse` to validate selectors from user input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let page = HtmlNode::parse_fragment(r#"<ul><li class="done">a</li><li>b</li></ul>"#).unwrap();
+    /// assert_eq!(page.select("ul > li.done").len(), 1);
+    /// ```
+    pub fn select(&self, selector: &str) -> Vec<&HtmlNode> {
+        select(None, std::slice::from_ref(self), selector)
+    }
+
+    /// Gets the first node matching a css selector.
+    ///
+    /// # Panics
+    ///
+    /// If the selector is invalid.
+    pub fn select_first(&self, selector: &str) -> Option<&HtmlNode> {
+        select_first(None, std::slice::from_ref(self), selector)
+    }
+
+    /// Calls `f` on every node matching a css selector. Nodes are visited in reverse document
+    /// order, so `f` can freely modify or replace them.
+    ///
+    /// # Panics
+    ///
+    /// If the selector is invalid.
+    pub fn select_mut(&mut self, selector: &str, f: impl FnMut(&mut HtmlNode)) {
+        let paths = select_paths_for(None, std::slice::from_ref(self), selector);
+        update_paths(std::slice::from_mut(self), &paths, f);
+    }
+
+    /// Gets the node with the given `id`
+    pub fn find_by_id(&self, id: &str) -> Option<&HtmlNode> {
+        find_by_id(None, std::slice::from_ref(self), id)
+    }
+
+    /// Gets the node with the given `id`
+    pub fn find_by_id_mut(&mut self, id: &str) -> Option<&mut HtmlNode> {
+        find_by_id_mut(std::slice::from_mut(self), id)
+    }
+}
+
+struct SelectorParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl SelectorParser<'_> {
+    fn run(mut self) -> Result<Selector, SelectorError> {
+        let mut alternatives = Vec::new();
+        loop {
+            self.skip_whitespace();
+            alternatives.push(self.parse_complex()?);
+            match self.peek() {
+                None => break,
+                Some(',') => self.pos += 1,
+                Some(c) => return Err(self.error(format!("unexpected `{c}`"))),
+            }
+        }
+        Ok(Selector { alternatives })
+    }
+
+    fn error(&self, message: impl Into<String>) -> SelectorError {
+        SelectorError {
+            message: message.into(),
+            position: self.pos,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Skips whitespace, returning if there was any
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+        self.pos > start
+    }
+
+    fn parse_complex(&mut self) -> Result<ComplexSelector, SelectorError> {
+        let mut compounds = vec![self.parse_compound()?];
+        let mut combinators = Vec::new();
+        loop {
+            let had_whitespace = self.skip_whitespace();
+            let combinator = match self.peek() {
+                None | Some(',') => break,
+                Some('>') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    Combinator::Child
+                }
+                Some(_) if had_whitespace => Combinator::Descendant,
+                Some(c) => return Err(self.error(format!("unexpected `{c}`"))),
+            };
+            combinators.push(combinator);
+            compounds.push(self.parse_compound()?);
+        }
+        Ok(ComplexSelector {
+            compounds,
+            combinators,
+        })
+    }
+
+    fn parse_compound(&mut self) -> Result<Compound, SelectorError> {
+        let mut compound = Compound::default();
+        let mut empty = true;
+        match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                empty = false;
+            }
+            Some(c) if is_ident_char(c) => {
+                compound.tag = Some(self.parse_ident()?);
+                empty = false;
+            }
+            _ => {}
+        }
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.pos += 1;
+                    compound.ids.push(self.parse_ident()?);
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    compound.classes.push(self.parse_ident()?);
+                }
+                Some('[') => {
+                    self.pos += 1;
+                    compound.attrs.push(self.parse_attr()?);
+                }
+                Some(':') => return Err(self.error("pseudo-classes are not supported")),
+                _ => break,
+            }
+            empty = false;
+        }
+        if empty {
+            return Err(match self.peek() {
+                Some(c) => self.error(format!("expected a selector, found `{c}`")),
+                None => self.error("expected a selector"),
+            });
+        }
+        Ok(compound)
+    }
+
+    /// Parses a name, handling backslash escapes
+    fn parse_ident(&mut self) -> Result<String, SelectorError> {
+        let mut ident = String::new();
+        while let Some(c) = self.peek() {
+            if c == '\\' {
+                self.pos += 1;
+                match self.bump() {
+                    Some(c) => ident.push(c),
+                    None => return Err(self.error("unfinished escape")),
+                }
+            } else if is_ident_char(c) {
+                self.pos += c.len_utf8();
+                ident.push(c);
+            } else {
+                break;
+            }
+        }
+        if ident.is_empty() {
+            return Err(self.error("expected a name"));
+        }
+        Ok(ident)
+    }
+
+    /// Parses an attribute selector after the `[`
+    fn parse_attr(&mut self) -> Result<AttrSelector, SelectorError> {
+        self.skip_whitespace();
+        let name = self.parse_ident()?;
+        self.skip_whitespace();
+        let op = match self.bump() {
+            Some(']') => return Ok(AttrSelector { name, op: None }),
+            Some('=') => AttrOp::Equals,
+            Some(c @ ('~' | '|' | '^' | '$' | '*')) if self.peek() == Some('=') => {
+                self.pos += 1;
+                match c {
+                    '~' => AttrOp::Includes,
+                    '|' => AttrOp::DashMatch,
+                    '^' => AttrOp::Prefix,
+                    '$' => AttrOp::Suffix,
+                    _ => AttrOp::Substring,
+                }
+            }
+            _ => return Err(self.error("invalid attribute selector")),
+        };
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.pos += 1;
+                let mut value = String::new();
+                loop {
+                    match self.bump() {
+                        Some(c) if c == quote => break,
+                        Some('\\') => match self.bump() {
+                            Some(c) => value.push(c),
+                            None => return Err(self.error("unfinished escape")),
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(self.error("unfinished string")),
+                    }
+                }
+                value
+            }
+            _ => self.parse_ident()?,
+        };
+        self.skip_whitespace();
+        if self.bump() != Some(']') {
+            return Err(self.error("expected `]`"));
+        }
+        Ok(AttrSelector {
+            name,
+            op: Some((op, value)),
+        })
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_') || !c.is_ascii()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        components::mk_card,
+        html_element::*,
+        node::{IntoNode, fragment},
+        render::Render,
+        svg::*,
+    };
+
+    fn page() -> HtmlNode {
+        HtmlNode::parse_fragment(
+            r#"<main id="main">
+  <div class="card">
+    <div class="card-body">
+      <h2 class="card-title">Title</h2>
+      <p lang="en-US">Intro <a href="https://example.com/doc.pdf">doc</a></p>
+      <section><h2>Nested</h2></section>
+    </div>
+  </div>
+  <form hx-post="/todo"><input type="text" name="title" required></form>
+  <svg viewBox="0 0 10 10"><g><circle id="dot" fill="red"></circle></g></svg>
+</main>"#,
+        )
+        .unwrap()
+    }
+
+    fn rendered(nodes: Vec<&HtmlNode>) -> String {
+        nodes
+            .into_iter()
+            .map(|o| o.render_compact())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn select_combinators() {
+        let page = page();
+        insta::assert_snapshot!(rendered(page.select("div.card-body > h2")), @r#"<h2 class="card-title">Title</h2>"#);
+        insta::assert_snapshot!(rendered(page.select(".card h2")), @r#"
+        <h2 class="card-title">Title</h2>
+        <h2>Nested</h2>
+        "#);
+        insta::assert_snapshot!(rendered(page.select("main > * > .card-body section > h2")), @"<h2>Nested</h2>");
+        assert!(page.select("main > h2").is_empty());
+        assert!(page.select("#main > .card-body").is_empty());
+    }
+
+    #[test]
+    fn select_attributes() {
+        let page = page();
+        let count = |selector| page.select(selector).len();
+        assert_eq!(count("[hx-post='/todo']"), 1);
+        assert_eq!(count("input[required]"), 1);
+        assert_eq!(count("input[type=text][name=\"title\"]"), 1);
+        assert_eq!(count("input[type=number]"), 0);
+        assert_eq!(count("[class~=card-body]"), 1);
+        assert_eq!(count("[class^=card]"), 3);
+        assert_eq!(count("a[href$='.pdf']"), 1);
+        assert_eq!(count("a[href*=example]"), 1);
+        assert_eq!(count("a[href^='']"), 0);
+        assert_eq!(count("[lang|=en]"), 1);
+        assert_eq!(count("[lang|=en-U]"), 0);
+        let el = div()
+            .add(p().style("color: red").style_prop("display", "none"))
+            .add(span().style_prop("color", "blue"));
+        let count = |selector| el.select(selector).len();
+        assert_eq!(count("[style]"), 2);
+        assert_eq!(count("[style*='display: none']"), 1);
+        assert_eq!(count("[style='color: red; display: none']"), 1);
+        assert_eq!(count("[style^=color]"), 2);
+    }
+
+    #[test]
+    fn select_svg_and_lists() {
+        let page = page();
+        insta::assert_snapshot!(rendered(page.select("svg g > circle[fill=red]")), @r#"<circle id="dot" fill="red"></circle>"#);
+        assert_eq!(page.select("[viewbox]").len(), 1);
+        insta::assert_snapshot!(rendered(page.select("circle, .card-title, a")), @r#"
+        <h2 class="card-title">Title</h2>
+        <a href="https://example.com/doc.pdf">doc</a>
+        <circle id="dot" fill="red"></circle>
+        "#);
+        assert_eq!(page.select("main").len(), 1);
+    }
+
+    #[test]
+    fn select_on_elements() {
+        let card = mk_card(Some("Title"), vec![p().add("a"), p().add("b")]);
+        assert_eq!(card.select(".card > .card-body > p").len(), 2);
+        assert!(card.select(".card").is_empty());
+        let title = card
+            .select_first("h2")
+            .and_then(|o| o.as_element())
+            .unwrap();
+        assert!(title.has_class("card-title"));
+
+        let icon = svg().add(svg_path().set_attr("d", "M0 0"));
+        assert_eq!(icon.select("svg > path").len(), 1);
+    }
+
+    #[test]
+    fn select_mut_works() {
+        let mut list = ul()
+            .add(li().class("a").add(ul().add(li().class("b"))))
+            .add(fragment([li().class("c")]));
+        list.select_mut("li", |o| {
+            if let Some(o) = o.as_element_mut() {
+                o.add_class_mut("item");
+            }
+        });
+        list.select_mut("li.b", |o| *o = span().add("replaced").into_node());
+        insta::assert_snapshot!(list.render(), @r#"
+        <ul>
+          <li class="a item">
+            <ul><span>replaced</span></ul>
+          </li>
+          <li class="c item"></li>
+        </ul>
+        "#);
+
+        let mut page = page();
+        page.select_mut(".card, h2", |o| *o = p().add("gone").into_node());
+        assert!(page.select("h2").is_empty());
+        assert_eq!(page.select("main > p").len(), 1);
+    }
+
+    #[test]
+    fn find_by_id_works() {
+        let mut page = page();
+        assert_eq!(
+            page.find_by_id("dot").unwrap().as_svg().unwrap().tag(),
+            "circle"
+        );
+        assert_eq!(
+            page.find_by_id("main").unwrap().as_element().unwrap().tag(),
+            "main"
+        );
+        assert!(page.find_by_id("missing").is_none());
+        if let Some(HtmlNode::SvgElement(dot)) = page.find_by_id_mut("dot") {
+            dot.set_attr_mut("fill", "blue");
+        }
+        assert_eq!(page.select("#dot[fill=blue]").len(), 1);
+
+        let mut form = form().add(input().id("name"));
+        form.find_by_id_mut("name")
+            .and_then(|o| o.as_element_mut())
+            .unwrap()
+            .set_empty_attr_mut("required");
+        assert_eq!(form.select("#name[required]").len(), 1);
+    }
+
+    #[test]
+    fn selector_parse_errors() {
+        let err = |selector| Selector::parse(selector).unwrap_err().to_string();
+        assert!(Selector::parse(r".md\:flex > a[href='x']").is_ok());
+        insta::assert_snapshot!(err(""), @"expected a selector at position 0");
+        insta::assert_snapshot!(err("div >"), @"expected a selector at position 5");
+        insta::assert_snapshot!(err("a:hover"), @"pseudo-classes are not supported at position 1");
+        insta::assert_snapshot!(err("a[href"), @"invalid attribute selector at position 6");
+        insta::assert_snapshot!(err("a[href='x"), @"unfinished string at position 9");
+        insta::assert_snapshot!(err("a, , b"), @"expected a selector, found `,` at position 3");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid selector `div[`")]
+    fn select_panics_on_invalid_selector() {
+        page().select("div[");
+    }
+}
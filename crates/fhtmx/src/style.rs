@@ -0,0 +1,403 @@
+use crate::{attribute::AttributeValue, element::Element};
+use indexmap::IndexMap;
+use std::{borrow::Cow, fmt};
+
+/// Values accepted by `Element::style_prop`
+pub trait IntoStyleValue {
+    /// Transforms into a css value
+    fn into_style_value(self) -> String;
+}
+
+macro_rules! implement_for_display {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl IntoStyleValue for $t {
+                fn into_style_value(self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+implement_for_display!(
+    &str,
+    &String,
+    String,
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+    f32,
+    f64,
+    CssLength,
+    CssDisplay,
+    CssPosition
+);
+
+impl IntoStyleValue for Cow<'_, str> {
+    fn into_style_value(self) -> String {
+        self.into_owned()
+    }
+}
+
+/// A css length (`width`, `margin`, `top`, ...)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CssLength {
+    Auto,
+    Px(f64),
+    Rem(f64),
+    Em(f64),
+    Percent(f64),
+    Vw(f64),
+    Vh(f64),
+}
+
+impl fmt::Display for CssLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CssLength::Auto => f.write_str("auto"),
+            CssLength::Px(x) => write!(f, "{x}px"),
+            CssLength::Rem(x) => write!(f, "{x}rem"),
+            CssLength::Em(x) => write!(f, "{x}em"),
+            CssLength::Percent(x) => write!(f, "{x}%"),
+            CssLength::Vw(x) => write!(f, "{x}vw"),
+            CssLength::Vh(x) => write!(f, "{x}vh"),
+        }
+    }
+}
+
+/// Values of the `display` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssDisplay {
+    None,
+    Block,
+    Inline,
+    InlineBlock,
+    Flex,
+    InlineFlex,
+    Grid,
+    InlineGrid,
+    Contents,
+}
+
+impl fmt::Display for CssDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CssDisplay::None => "none",
+            CssDisplay::Block => "block",
+            CssDisplay::Inline => "inline",
+            CssDisplay::InlineBlock => "inline-block",
+            CssDisplay::Flex => "flex",
+            CssDisplay::InlineFlex => "inline-flex",
+            CssDisplay::Grid => "grid",
+            CssDisplay::InlineGrid => "inline-grid",
+            CssDisplay::Contents => "contents",
+        })
+    }
+}
+
+/// Values of the `position` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssPosition {
+    Static,
+    Relative,
+    Absolute,
+    Fixed,
+    Sticky,
+}
+
+impl fmt::Display for CssPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CssPosition::Static => "static",
+            CssPosition::Relative => "relative",
+            CssPosition::Absolute => "absolute",
+            CssPosition::Fixed => "fixed",
+            CssPosition::Sticky => "sticky",
+        })
+    }
+}
+
+/// Merges the declarations of the `style` attribute with the style properties, the properties
+/// go last so they take precedence
+pub(crate) fn merge_style(
+    attr: Option<&AttributeValue>,
+    styles: &IndexMap<Cow<'static, str>, String>,
+) -> String {
+    let mut res = match attr {
+        Some(AttributeValue::Value(x) | AttributeValue::Raw(x)) => {
+            x.trim().trim_end_matches(';').to_string()
+        }
+        _ => String::new(),
+    };
+    // Names can't be checked when the properties are deserialized, so invalid ones are skipped
+    for (k, v) in styles.iter().filter(|(k, _)| is_css_property_name(k)) {
+        if !res.is_empty() {
+            res.push_str("; ");
+        }
+        res.push_str(k);
+        res.push_str(": ");
+        res.push_str(&escape_style_value(v.trim().trim_end_matches(';')));
+    }
+    res
+}
+
+/// Value of the `style` attribute as it's rendered, merged with the style properties
+pub(crate) fn style_attr_value<'a>(
+    attr: Option<&'a AttributeValue>,
+    styles: Option<&IndexMap<Cow<'static, str>, String>>,
+) -> Option<Cow<'a, str>> {
+    match (attr, styles.filter(|o| !o.is_empty())) {
+        (attr, Some(styles)) => Some(Cow::Owned(merge_style(attr, styles))),
+        (Some(AttributeValue::Empty), None) => Some(Cow::Borrowed("")),
+        (Some(AttributeValue::Value(x) | AttributeValue::Raw(x)), None) => Some(Cow::Borrowed(x)),
+        (None, None) => None,
+    }
+}
+
+/// Removes the declarations of the property `name` from a `style` attribute
+pub(crate) fn remove_declaration(style: &str, name: &str) -> String {
+    style
+        .split(';')
+        .filter(|o| {
+            o.split_once(':')
+                .is_none_or(|(k, _)| !k.trim().eq_ignore_ascii_case(name))
+        })
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Checks that `name` is a css property name (eg: `color`, `-webkit-line-clamp`, `--gap`)
+pub(crate) fn is_css_property_name(name: &str) -> bool {
+    let rest = name
+        .strip_prefix("--")
+        .or_else(|| name.strip_prefix('-'))
+        .unwrap_or(name);
+    let custom = name.starts_with("--");
+    rest.bytes().next().is_some_and(|o| {
+        o.is_ascii_alphabetic() || o == b'_' || (custom && (o.is_ascii_digit() || o == b'-'))
+    }) && rest
+        .bytes()
+        .all(|o| o.is_ascii_alphanumeric() || matches!(o, b'-' | b'_'))
+}
+
+/// Checks that `value` stays inside its declaration: strings and brackets are closed and there
+/// are no `;`, `{` or `}` outside of them
+fn is_contained_style_value(value: &str) -> bool {
+    let mut brackets = Vec::new();
+    let mut quote = None;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            // Skips the escaped char, a trailing `\` would escape the next declaration
+            (_, '\\') if chars.next().is_none_or(|o| o == '\n') => return false,
+            (_, '\\') => {}
+            (Some(_), '\n') => return false,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => brackets.push(')'),
+            (None, '[') => brackets.push(']'),
+            (None, ')' | ']') if brackets.pop() != Some(c) => return false,
+            (None, '{' | '}') => return false,
+            (None, ';') if brackets.is_empty() => return false,
+            _ => {}
+        }
+    }
+    quote.is_none() && brackets.is_empty()
+}
+
+/// Escapes the characters that could end the declaration of `value` when it's not contained (see
+/// `is_contained_style_value`), so browsers drop it instead of reading other declarations from it
+fn escape_style_value(value: &str) -> Cow<'_, str> {
+    if is_contained_style_value(value) {
+        return Cow::Borrowed(value);
+    }
+    let mut res = String::with_capacity(value.len() + 8);
+    for c in value.chars() {
+        match c {
+            ';' | '{' | '}' | '(' | ')' | '[' | ']' | '"' | '\'' | '\\' => {
+                res.push('\\');
+                res.push(c);
+            }
+            '\n' => res.push_str("\\a "),
+            _ => res.push(c),
+        }
+    }
+    Cow::Owned(res)
+}
+
+/// Gets the value of a css property from the style properties or the `style` attribute
+pub(crate) fn get_style<'a, E: Element>(el: &'a E, name: &str) -> Option<&'a str> {
+    if let Some(x) = el.styles().and_then(|o| o.get(name)) {
+        return Some(x.trim());
+    }
+    let (AttributeValue::Value(style) | AttributeValue::Raw(style)) = el.attrs().get("style")?
+    else {
+        return None;
+    };
+    // The last declaration wins
+    style
+        .rsplit(';')
+        .filter_map(|o| o.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{html_element::*, node::HtmlNode, render::Render, svg::*};
+    use indexmap::IndexSet;
+
+    #[test]
+    fn style_props_merge() {
+        let res = div()
+            .style("color: red;")
+            .style_prop("width", CssLength::Percent(50.0))
+            .style_prop("margin", CssLength::Rem(1.5))
+            .style_prop("position", CssPosition::Sticky)
+            .style_prop("z-index", 10)
+            .style_prop("width", CssLength::Auto)
+            .remove_style_prop("margin")
+            .add(
+                span()
+                    .style_prop("font-family", r#""Fira Code", monospace"#)
+                    .style_prop("background", "url('/a.png?x=1&y=2')"),
+            )
+            .add(svg().style_prop("display", CssDisplay::None))
+            .render();
+        insta::assert_snapshot!(res, @r#"
+        <div style="color: red; width: auto; position: sticky; z-index: 10">
+          <span style="font-family: &quot;Fira Code&quot;, monospace; background: url(&#x27;/a.png?x=1&amp;y=2&#x27;)"></span>
+          <svg xmlns="http://www.w3.org/2000/svg" style="display: none"></svg>
+        </div>
+        "#);
+    }
+
+    #[test]
+    fn hostile_style_values_stay_in_their_declaration() {
+        let cases = [
+            ("red; position: fixed", r"red\; position: fixed"),
+            ("red } * { color: blue", r"red \} * \{ color: blue"),
+            (r#""unclosed; top: 0"#, r#"\"unclosed\; top: 0"#),
+            ("url(x)); top: 0", r"url\(x\)\)\; top: 0"),
+            ("a\\", r"a\\"),
+            (
+                r#"url("data:image/png;base64,AA==")"#,
+                r#"url("data:image/png;base64,AA==")"#,
+            ),
+            (
+                "url(data:image/png;base64,AA==)",
+                "url(data:image/png;base64,AA==)",
+            ),
+            (r#""a;b\"c", 'd}'"#, r#""a;b\"c", 'd}'"#),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(escape_style_value(value), expected, "{value}");
+        }
+        let mut el = div().style("color: red");
+        el.styles.insert("x; position: fixed".into(), "0".into());
+        let res = el
+            .style_prop("background", "url(a.png); position: fixed; inset: 0")
+            .render();
+        insta::assert_snapshot!(res, @r#"<div style="color: red; background: url\(a.png\)\; position: fixed\; inset: 0"></div>"#);
+    }
+
+    /// Element that doesn't store style properties
+    struct Widget {
+        attrs: IndexMap<Cow<'static, str>, AttributeValue>,
+        classes: IndexSet<Cow<'static, str>>,
+        children: Vec<HtmlNode>,
+    }
+
+    impl Element for Widget {
+        fn tag(&self) -> &str {
+            "x-widget"
+        }
+
+        fn attrs(&self) -> &IndexMap<Cow<'static, str>, AttributeValue> {
+            &self.attrs
+        }
+
+        fn attrs_mut(&mut self) -> &mut IndexMap<Cow<'static, str>, AttributeValue> {
+            &mut self.attrs
+        }
+
+        fn classes(&self) -> &IndexSet<Cow<'static, str>> {
+            &self.classes
+        }
+
+        fn classes_mut(&mut self) -> &mut IndexSet<Cow<'static, str>> {
+            &mut self.classes
+        }
+
+        fn children(&self) -> &[HtmlNode] {
+            &self.children
+        }
+
+        fn children_mut(&mut self) -> &mut Vec<HtmlNode> {
+            &mut self.children
+        }
+
+        fn is_void_tag(&self) -> bool {
+            false
+        }
+
+        fn is_inline_tag(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn style_props_merge_into_the_attribute_without_storage() {
+        let el = Widget {
+            attrs: IndexMap::new(),
+            classes: Default::default(),
+            children: Vec::new(),
+        }
+        .set_attr("style", "color: red")
+        .style_prop("width", CssLength::Px(10.0))
+        .style_prop("display", CssDisplay::Flex)
+        .style_prop("color", "blue")
+        .remove_style_prop("display");
+        let style = style_attr_value(el.attrs().get("style"), el.styles());
+        assert_eq!(style.as_deref(), Some("width: 10px; color: blue"));
+        assert_eq!(get_style(&el, "color"), Some("blue"));
+    }
+
+    #[test]
+    fn css_property_names() {
+        for name in ["color", "-webkit-line-clamp", "--gap", "--1x", "_x"] {
+            assert!(is_css_property_name(name), "{name}");
+        }
+        for name in ["", "-", "--", "1x", "-1x", "co lor", "a:b", "a;b"] {
+            assert!(!is_css_property_name(name), "{name}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid css property name: `color;top`")]
+    fn style_prop_rejects_invalid_names() {
+        div().style_prop("color;top", "0");
+    }
+
+    #[test]
+    fn get_style_works() {
+        let el = div().style("white-space: normal; COLOR: red; color: blue");
+        assert_eq!(get_style(&el, "color"), Some("blue"));
+        assert_eq!(get_style(&el, "white-space"), Some("normal"));
+        let el = el.style_prop("white-space", "pre");
+        assert_eq!(get_style(&el, "white-space"), Some("pre"));
+        assert_eq!(get_style(&el, "width"), None);
+    }
+}
@@ -1,7 +1,9 @@
 use crate::{
     attribute::{AttributeValue, IntoAttributeValue},
-    element::{Element, set_attr},
-    node::{HtmlNode, IntoNode},
+    element::{Element, set_attr, set_url_attr},
+    html_element::{InvalidTagName, is_valid_tag_name},
+    node::{HtmlNode, IntoNode, clone_nodes, drop_nodes},
+    safe_url::IntoUrlValue,
 };
 use indexmap::{IndexMap, IndexSet};
 use pastey::paste;
@@ -9,30 +11,146 @@ use std::borrow::Cow;
 
 pub const SVG_INLINE_ELEMENTS: &[&str] = &["text", "tspan"];
 
+/// Every svg element with a constructor in this crate
+pub const SVG_ELEMENTS: &[&str] = &[
+    "a",
+    "animate",
+    "animateMotion",
+    "animateTransform",
+    "circle",
+    "clipPath",
+    "defs",
+    "desc",
+    "ellipse",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feConvolveMatrix",
+    "feDiffuseLighting",
+    "feDisplacementMap",
+    "feDistantLight",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feImage",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+    "fePointLight",
+    "feSpecularLighting",
+    "feSpotLight",
+    "feTile",
+    "feTurbulence",
+    "filter",
+    "foreignObject",
+    "g",
+    "image",
+    "line",
+    "linearGradient",
+    "marker",
+    "mask",
+    "metadata",
+    "mpath",
+    "path",
+    "pattern",
+    "polygon",
+    "polyline",
+    "radialGradient",
+    "rect",
+    "script",
+    "set",
+    "stop",
+    "style",
+    "svg",
+    "switch",
+    "symbol",
+    "text",
+    "textPath",
+    "title",
+    "tspan",
+    "use",
+    "view",
+];
+
 /// Represents a HTML element
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct SvgElement {
-    pub tag: &'static str,
+    pub tag: Cow<'static, str>,
     pub attrs: IndexMap<Cow<'static, str>, AttributeValue>,
     pub classes: IndexSet<Cow<'static, str>>,
+    pub(crate) styles: IndexMap<Cow<'static, str>, String>,
     pub children: Vec<HtmlNode>,
 }
 
 impl SvgElement {
-    pub fn new(tag: &'static str) -> Self {
+    /// Clones the element without its children
+    pub(crate) fn clone_shell(&self) -> Self {
         Self {
+            tag: self.tag.clone(),
+            attrs: self.attrs.clone(),
+            classes: self.classes.clone(),
+            styles: self.styles.clone(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Clones the children without recursion
+impl Clone for SvgElement {
+    fn clone(&self) -> Self {
+        let mut el = self.clone_shell();
+        el.children = clone_nodes(&self.children);
+        el
+    }
+}
+
+/// Drops the children without recursion
+impl Drop for SvgElement {
+    fn drop(&mut self) {
+        drop_nodes(&mut self.children);
+    }
+}
+
+impl SvgElement {
+    /// Creates an element with the given tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` is not a valid tag name, see `is_valid_tag_name`.
+    #[track_caller]
+    pub fn new(tag: impl Into<Cow<'static, str>>) -> Self {
+        match Self::try_new(tag) {
+            Ok(o) => o,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Creates an element with the given tag, fails if `tag` is not a valid tag name.
+    pub fn try_new(tag: impl Into<Cow<'static, str>>) -> Result<Self, InvalidTagName> {
+        let tag = tag.into();
+        if !is_valid_tag_name(&tag) {
+            return Err(InvalidTagName(tag.into_owned()));
+        }
+        Ok(Self {
             tag,
             attrs: IndexMap::new(),
             classes: IndexSet::new(),
+            styles: IndexMap::new(),
             children: Vec::new(),
-        }
+        })
     }
 }
 
 impl Element for SvgElement {
     #[inline]
-    fn tag(&self) -> &'static str {
-        self.tag
+    fn tag(&self) -> &str {
+        &self.tag
     }
 
     #[inline]
@@ -55,6 +173,16 @@ impl Element for SvgElement {
         &mut self.classes
     }
 
+    #[inline]
+    fn styles(&self) -> Option<&IndexMap<Cow<'static, str>, String>> {
+        Some(&self.styles)
+    }
+
+    #[inline]
+    fn styles_mut(&mut self) -> Option<&mut IndexMap<Cow<'static, str>, String>> {
+        Some(&mut self.styles)
+    }
+
     #[inline]
     fn children(&self) -> &[HtmlNode] {
         &self.children
@@ -188,6 +316,8 @@ create_svg_fn!(
 );
 
 impl SvgElement {
+    set_url_attr!(URL_ATTR_SETTERS: href, xlink_href = "xlink:href");
+
     set_attr!(
         alignment_baseline = "alignment-baseline",
         baseline_shift = "baseline-shift",
@@ -261,7 +391,6 @@ impl SvgElement {
         x2,
         xlink_actuate = "xlink:actuate",
         xlink_arcrole = "xlink:arcrole",
-        xlink_href = "xlink:href",
         xlink_role = "xlink:role",
         xlink_show = "xlink:show",
         xlink_title = "xlink:title",
@@ -0,0 +1,442 @@
+//! Assertions to test html by its structure instead of its exact rendered bytes.
+//!
+//! Every assertion takes anything implementing `AsHtmlNode`: nodes, elements or a rendered body
+//! (`str`, `String` or bytes), which gets parsed first. Elements are found with css selectors
+//! (see `Selector`) and text is compared with its whitespace collapsed, so changes in indentation
+//! or attribute order don't break the tests.
+//!
+//! ```
+//! # use fhtmx::prelude::*;
+//! use fhtmx::testing::*;
+//!
+//! let body = r#"<form hx-post="/todo"><input name="title"><button>Add  todo</button></form>"#;
+//! assert_has_element(body, "form[hx-post='/todo'] > button");
+//! assert_text_contains(body, "button", "Add todo");
+//! assert_attr(body, "input", "name", "title");
+//! assert_count(body, "input, button", 2);
+//! ```
+
+use crate::{
+    a11y::CheckA11y, attribute::AttributeValue, element::Element, html_element::HtmlElement,
+    node::HtmlNode, render::Render, style::style_attr_value, svg::SvgElement, validate::Validate,
+};
+use std::borrow::Cow;
+
+/// Values that can be checked by the `testing` assertions
+pub trait AsHtmlNode {
+    /// Gets the node to check, parsing it if needed. The markup of `HtmlNode::Raw` and
+    /// `HtmlNode::Prerendered` nodes is parsed too, so the elements inside them can be found.
+    ///
+    /// # Panics
+    ///
+    /// If the html can't be parsed.
+    fn as_html_node(&self) -> Cow<'_, HtmlNode>;
+}
+
+impl AsHtmlNode for HtmlNode {
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        parse_raw_nodes(Cow::Borrowed(self))
+    }
+}
+
+impl AsHtmlNode for HtmlElement {
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        parse_raw_nodes(Cow::Owned(HtmlNode::Element(self.clone())))
+    }
+}
+
+impl AsHtmlNode for SvgElement {
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        parse_raw_nodes(Cow::Owned(HtmlNode::SvgElement(self.clone())))
+    }
+}
+
+impl AsHtmlNode for str {
+    #[track_caller]
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        match HtmlNode::parse(self) {
+            Ok(node) => Cow::Owned(node),
+            Err(e) => panic!("failed to parse html: {e}\n{self}"),
+        }
+    }
+}
+
+impl AsHtmlNode for String {
+    #[track_caller]
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        self.as_str().as_html_node()
+    }
+}
+
+impl AsHtmlNode for [u8] {
+    #[track_caller]
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        match std::str::from_utf8(self) {
+            Ok(s) => s.as_html_node(),
+            Err(e) => panic!("html body is not valid utf-8: {e}"),
+        }
+    }
+}
+
+impl AsHtmlNode for Vec<u8> {
+    #[track_caller]
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        self.as_slice().as_html_node()
+    }
+}
+
+impl<T: AsHtmlNode + ?Sized> AsHtmlNode for &T {
+    #[track_caller]
+    fn as_html_node(&self) -> Cow<'_, HtmlNode> {
+        (**self).as_html_node()
+    }
+}
+
+/// Replaces the raw and pre-rendered nodes by their parsed markup, the ones that can't be parsed
+/// are kept
+fn parse_raw_nodes(node: Cow<'_, HtmlNode>) -> Cow<'_, HtmlNode> {
+    if !has_raw_nodes(&node) {
+        return node;
+    }
+    let mut node = node.into_owned();
+    for raw in raw_nodes_mut(&mut node) {
+        let html = match raw {
+            HtmlNode::Raw(x) => x.as_str(),
+            HtmlNode::Prerendered(x) => x.as_str(),
+            _ => continue,
+        };
+        if let Ok(parsed) = HtmlNode::parse(html) {
+            *raw = parsed;
+        }
+    }
+    Cow::Owned(node)
+}
+
+fn has_raw_nodes(node: &HtmlNode) -> bool {
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        match node {
+            HtmlNode::Raw(_) | HtmlNode::Prerendered(_) => return true,
+            HtmlNode::Element(x) if !x.is_raw_text_tag() => stack.extend(&x.children),
+            HtmlNode::SvgElement(x) => stack.extend(&x.children),
+            HtmlNode::Fragment(x) => stack.extend(x),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Raw and pre-rendered nodes, except the content of `script` and `style`
+fn raw_nodes_mut(node: &mut HtmlNode) -> Vec<&mut HtmlNode> {
+    let mut res = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        match node {
+            HtmlNode::Raw(_) | HtmlNode::Prerendered(_) => res.push(node),
+            HtmlNode::Element(x) if !x.is_raw_text_tag() => stack.extend(&mut x.children),
+            HtmlNode::SvgElement(x) => stack.extend(&mut x.children),
+            HtmlNode::Fragment(x) => stack.extend(x),
+            _ => {}
+        }
+    }
+    res
+}
+
+/// Text of a node and its descendants, with whitespace collapsed. Block elements are separated
+/// by a space, raw html is parsed and the content of `script` and `style` is skipped.
+pub fn text_content(node: &HtmlNode) -> String {
+    let mut text = String::new();
+    push_text(node, &mut text, true);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn push_text(node: &HtmlNode, text: &mut String, parse_raw: bool) {
+    match node {
+        HtmlNode::Text(x) => text.push_str(x),
+        HtmlNode::Element(x) => push_element_text(x, text, parse_raw),
+        HtmlNode::SvgElement(x) => push_element_text(x, text, parse_raw),
+        HtmlNode::Fragment(x) => x.iter().for_each(|o| push_text(o, text, parse_raw)),
+        // Comments and doctypes parse to raw nodes too, so they are only parsed once
+        HtmlNode::Raw(x) if parse_raw => push_raw_text(x, text),
+        HtmlNode::Prerendered(x) if parse_raw => push_raw_text(x.as_str(), text),
+        _ => {}
+    }
+}
+
+fn push_raw_text(html: &str, text: &mut String) {
+    if let Ok(node) = HtmlNode::parse_fragment(html) {
+        push_text(&node, text, false);
+    }
+}
+
+fn push_element_text<E: Element>(el: &E, text: &mut String, parse_raw: bool) {
+    if el.is_raw_text_tag() {
+        return;
+    }
+    let block = !el.is_inline_tag();
+    if block {
+        text.push(' ');
+    }
+    for child in el.children() {
+        push_text(child, text, parse_raw);
+    }
+    if block {
+        text.push(' ');
+    }
+}
+
+fn attr_value<'a>(node: &'a HtmlNode, attr: &str) -> Option<Cow<'a, str>> {
+    let (attrs, classes, styles) = match node {
+        HtmlNode::Element(x) => (x.attrs(), x.classes(), x.styles()),
+        HtmlNode::SvgElement(x) => (x.attrs(), x.classes(), x.styles()),
+        _ => return None,
+    };
+    if attr == "style" {
+        return style_attr_value(attrs.get("style"), styles);
+    }
+    if attr == "class" {
+        let classes = classes.iter().map(|o| o.as_ref()).collect::<Vec<_>>();
+        return (!classes.is_empty()).then(|| classes.join(" ").into());
+    }
+    attrs.get(attr).map(|o| match o {
+        AttributeValue::Empty => "".into(),
+        AttributeValue::Value(x) | AttributeValue::Raw(x) => x.as_str().into(),
+    })
+}
+
+fn render_all(nodes: &[&HtmlNode]) -> String {
+    nodes
+        .iter()
+        .map(|o| o.render())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Asserts that some element matches `selector`
+#[track_caller]
+pub fn assert_has_element(html: impl AsHtmlNode, selector: &str) {
+    let node = html.as_html_node();
+    if node.select_first(selector).is_none() {
+        panic!(
+            "expected an element matching `{selector}`, found none in:\n{}",
+            node.render()
+        );
+    }
+}
+
+/// Asserts that no element matches `selector`
+#[track_caller]
+pub fn assert_no_element(html: impl AsHtmlNode, selector: &str) {
+    let node = html.as_html_node();
+    let found = node.select(selector);
+    if !found.is_empty() {
+        panic!(
+            "expected no element matching `{selector}`, found {}:\n{}",
+            found.len(),
+            render_all(&found)
+        );
+    }
+}
+
+/// Asserts that exactly `count` elements match `selector`
+#[track_caller]
+pub fn assert_count(html: impl AsHtmlNode, selector: &str, count: usize) {
+    let node = html.as_html_node();
+    let found = node.select(selector);
+    if found.len() != count {
+        panic!(
+            "expected {count} elements matching `{selector}`, found {}:\n{}",
+            found.len(),
+            render_all(&found)
+        );
+    }
+}
+
+/// Asserts that the text of some element matching `selector` contains `text`. Whitespace is
+/// collapsed on both sides before comparing.
+#[track_caller]
+pub fn assert_text_contains(html: impl AsHtmlNode, selector: &str, text: &str) {
+    let node = html.as_html_node();
+    let found = node.select(selector);
+    if found.is_empty() {
+        panic!(
+            "expected an element matching `{selector}`, found none in:\n{}",
+            node.render()
+        );
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let texts = found.iter().map(|o| text_content(o)).collect::<Vec<_>>();
+    if !texts.iter().any(|o| o.contains(&text)) {
+        panic!(
+            "expected an element matching `{selector}` to contain {text:?}, found texts: {texts:?}"
+        );
+    }
+}
+
+/// Asserts that some element matching `selector` has the attribute `attr` set to `value`. For
+/// `class`, the value is the space separated list of classes.
+#[track_caller]
+pub fn assert_attr(html: impl AsHtmlNode, selector: &str, attr: &str, value: &str) {
+    let node = html.as_html_node();
+    let found = node.select(selector);
+    if found.is_empty() {
+        panic!(
+            "expected an element matching `{selector}`, found none in:\n{}",
+            node.render()
+        );
+    }
+    let values = found
+        .iter()
+        .map(|o| attr_value(o, attr))
+        .collect::<Vec<_>>();
+    if !values.iter().any(|o| o.as_deref() == Some(value)) {
+        panic!(
+            "expected an element matching `{selector}` with `{attr}` = {value:?}, found: {values:?}"
+        );
+    }
+}
+
+/// Asserts that the html follows the content-model rules checked by `Validate`. Rendered bodies
+/// are parsed first, which repairs some of the errors (eg: a `div` in a `p`), so prefer checking
+/// nodes or elements.
+#[track_caller]
+pub fn assert_valid_html(html: impl AsHtmlNode) {
+    let node = html.as_html_node();
+    if let Err(errors) = node.validate() {
+        let errors = errors
+            .iter()
+            .map(|o| format!("- {o}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        panic!("invalid html:\n{errors}\nin:\n{}", node.render());
+    }
+}
+
+/// Asserts that `CheckA11y` finds no accessibility issues
+#[track_caller]
+pub fn assert_a11y(html: impl AsHtmlNode) {
+    let node = html.as_html_node();
+    let report = node.check_a11y();
+    if !report.is_empty() {
+        panic!("accessibility issues:\n{report}\nin:\n{}", node.render());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        html_element::*,
+        node::{IntoNode, raw_node},
+        render::Render,
+    };
+
+    fn partial() -> HtmlElement {
+        form()
+            .hx_post("/todo")
+            .add(input().set_attr("type", "text").name("title").required())
+            .add(
+                button()
+                    .class("btn btn-primary")
+                    .add("Add ")
+                    .add(b().add("todo")),
+            )
+    }
+
+    #[test]
+    fn assertions_work_on_nodes_and_bodies() {
+        let el = partial();
+        let body = el.render();
+        let bytes = body.clone().into_bytes();
+        assert_has_element(&el, "button[class~=btn-primary]");
+        assert_has_element(&body, "form[hx-post='/todo'] > button");
+        assert_has_element(bytes.as_slice(), "input[required]");
+        assert_no_element(&body, "a");
+        assert_count(&el, "input, button", 2);
+        assert_count(body.as_str(), "form *", 3);
+        assert_text_contains(&el, "button", "Add todo");
+        assert_text_contains(&body, "form", "  Add\ntodo ");
+        assert_attr(&el, "input", "name", "title");
+        assert_attr(&body, "input", "required", "");
+        assert_attr(&body, "button", "class", "btn btn-primary");
+        let el = el.style("color: red").style_prop("display", "none");
+        assert_attr(&el, "form", "style", "color: red; display: none");
+        assert_valid_html(&el);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "accessibility issues:\nlabel: `input` without a label at `form > input[0]`"
+    )]
+    fn assert_a11y_fails() {
+        assert_a11y(partial());
+    }
+
+    #[test]
+    fn text_content_works() {
+        let node =
+            HtmlNode::parse_fragment("<p>\n  Hello   <b>big</b>\n<!-- x --> world\n</p>").unwrap();
+        assert_eq!(text_content(&node), "Hello big world");
+    }
+
+    #[test]
+    fn text_content_separates_blocks() {
+        let node = HtmlNode::parse_fragment(
+            "<tr><td>1</td><td>2</td></tr><p>a<b>b</b></p><div>c<script>let x = 1;</script></div>",
+        )
+        .unwrap();
+        assert_eq!(text_content(&node), "1 2 ab c");
+        let node = div()
+            .add(raw_node("<li>one</li><li>t<i>wo</i></li>"))
+            .add(span().add("three").into_node().freeze())
+            .into_node();
+        assert_eq!(text_content(&node), "one two three");
+    }
+
+    #[test]
+    fn assertions_parse_raw_nodes() {
+        let navbar = nav()
+            .add(a().href("/").add("Home"))
+            .add(a().href("/about").add("About"))
+            .into_node()
+            .freeze();
+        let page = body().add(navbar).add(main_tag().add("Content"));
+        assert_has_element(&page, "body > nav");
+        assert_count(&page, "nav a", 2);
+        assert_attr(&page, "nav > a", "href", "/about");
+        let node = HtmlNode::Raw("<nav><a href=\"/\">Home</a></nav>".to_string());
+        assert_has_element(&node, "nav");
+        assert_text_contains(&node, "nav", "Home");
+        assert_no_element(div().add(script().add_raw("let x = '<nav>';")), "nav");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected an element matching `button.btn-error`, found none")]
+    fn assert_has_element_fails() {
+        assert_has_element(partial(), "button.btn-error");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 1 elements matching `input, button`, found 2")]
+    fn assert_count_fails() {
+        assert_count(partial(), "input, button", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"to contain "Remove", found texts: ["Add todo"]"#)]
+    fn assert_text_contains_fails() {
+        assert_text_contains(partial(), "button", "Remove");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"with `name` = "body", found: [Some("title")]"#)]
+    fn assert_attr_fails() {
+        assert_attr(partial(), "input", "name", "body");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid html:\n- `form` inside `form` at `form > form[2]`")]
+    fn assert_valid_html_fails() {
+        assert_valid_html(partial().add(form()));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{entities::named_entity, render::RenderOptions};
+use std::fmt;
 use uuid::Uuid;
 
 pub fn escape_html(s: &str) -> String {
@@ -7,30 +9,171 @@ pub fn escape_html(s: &str) -> String {
 }
 
 pub fn escape_html_to(s: &str, buf: &mut String) {
-    for c in s.chars() {
-        match c {
-            '&' => buf.push_str("&amp;"),
-            '<' => buf.push_str("&lt;"),
-            '>' => buf.push_str("&gt;"),
-            '"' => buf.push_str("&quot;"),
-            '\'' => buf.push_str("&#x27;"),
-            _ => buf.push(c),
+    // Writing to a `String` never fails
+    let _ = write_escaped_html(s, buf);
+}
+
+pub fn escape_html_to_with_indent(s: &str, buf: &mut String, indent: usize) {
+    let _ = write_escaped_html_with_indent(s, buf, indent);
+}
+
+/// Decodes html character references (`&amp;`, `&#39;`, `&#x27;`, `&alpha;`, ...), named ones
+/// use the full html5 list. Unknown references are kept as they are.
+pub fn unescape_html(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        buf.push_str(&rest[..i]);
+        rest = &rest[i..];
+        match decode_char_ref(rest, &mut buf) {
+            Some(len) => rest = &rest[len..],
+            None => {
+                buf.push('&');
+                rest = &rest[1..];
+            }
         }
     }
+    buf.push_str(rest);
+    buf
 }
 
-pub fn escape_html_to_with_indent(s: &str, buf: &mut String, indent: usize) {
+/// Decodes the character reference at the start of `s` into `buf`, returning the consumed length
+fn decode_char_ref(s: &str, buf: &mut String) -> Option<usize> {
+    // References are short, so we don't need to look far for the `;`
+    let end = s.bytes().skip(1).take(32).position(|b| b == b';')? + 1;
+    let name = &s[1..end];
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        buf.push(char::from_u32(code).filter(|&c| c != '\0')?);
+    } else {
+        buf.push_str(named_entity(name)?);
+    }
+    Some(end + 1)
+}
+
+/// Writes `s` into `w` escaping html special characters
+pub fn write_escaped_html<W: fmt::Write + ?Sized>(s: &str, w: &mut W) -> fmt::Result {
+    let mut last = 0;
+    for (i, c) in s.char_indices() {
+        let escaped = match c {
+            '&' => "&amp;",
+            '<' => "&lt;",
+            '>' => "&gt;",
+            '"' => "&quot;",
+            '\'' => "&#x27;",
+            _ => continue,
+        };
+        w.write_str(&s[last..i])?;
+        w.write_str(escaped)?;
+        last = i + 1;
+    }
+    w.write_str(&s[last..])
+}
+
+/// Writes `s` into `w` escaping html special characters and indenting each line
+pub fn write_escaped_html_with_indent<W: fmt::Write + ?Sized>(
+    s: &str,
+    w: &mut W,
+    indent: usize,
+) -> fmt::Result {
     if indent == 0 {
-        escape_html_to(s, buf);
+        return write_escaped_html(s, w);
+    }
+    for (i, line) in s.lines().enumerate() {
+        if i > 0 {
+            w.write_char('\n')?;
+        }
+        write_indent(w, indent)?;
+        write_escaped_html(line, w)?;
+    }
+    Ok(())
+}
+
+/// Writes the padding for an indentation level
+#[inline]
+fn write_indent<W: fmt::Write + ?Sized>(w: &mut W, indent: usize) -> fmt::Result {
+    for _ in 0..indent {
+        w.write_str("  ")?;
+    }
+    Ok(())
+}
+
+/// Writes each line of `s` with the indentation from `opts`, optionally escaping it
+pub(crate) fn write_html_lines<W: fmt::Write + ?Sized>(
+    s: &str,
+    w: &mut W,
+    indent: usize,
+    opts: &RenderOptions,
+    escape: bool,
+) -> fmt::Result {
+    let write = |line: &str, w: &mut W| {
+        if escape {
+            write_escaped_html(line, w)
+        } else {
+            w.write_str(line)
+        }
+    };
+    if indent == 0 || !opts.newline {
+        return write(s, w);
+    }
+    for (i, line) in s.lines().enumerate() {
+        if i > 0 {
+            w.write_char('\n')?;
+        }
+        opts.write_indent(w, indent)?;
+        write(line, w)?;
+    }
+    Ok(())
+}
+
+/// Writes `s` as the content of a CDATA section, splitting the `]]>` sequences that would end it
+pub(crate) fn write_cdata<W: fmt::Write + ?Sized>(s: &str, w: &mut W) -> fmt::Result {
+    for (i, part) in s.split("]]>").enumerate() {
+        if i > 0 {
+            w.write_str("]]]]><![CDATA[>")?;
+        }
+        w.write_str(part)?;
+    }
+    Ok(())
+}
+
+/// Writes the content of the raw text element `tag` (`script` or `style`) without escaping it,
+/// except for the sequences that would end the element early: `</tag` becomes `<\/tag` and, in
+/// scripts, a `<!--` followed by a `<script` (which would hide the next `</script>` from the
+/// browser) becomes `\u003c!--`. Both are valid escapes in js and json strings, other `<!--`
+/// are kept since escaping them outside a string would be a js syntax error.
+pub(crate) fn write_raw_text<W: fmt::Write + ?Sized>(s: &str, tag: &str, w: &mut W) -> fmt::Result {
+    let last_script = if tag == "script" {
+        s.rmatch_indices('<').map(|(i, _)| i).find(|&i| {
+            s[i + 1..]
+                .get(..6)
+                .is_some_and(|o| o.eq_ignore_ascii_case("script"))
+        })
     } else {
-        let pad = "  ".repeat(indent);
-        s.lines().for_each(|o| {
-            buf.push_str(&pad);
-            escape_html_to(o, buf);
-            buf.push('\n');
-        });
-        buf.pop();
+        None
+    };
+    let mut last = 0;
+    for (i, _) in s.match_indices('<') {
+        let rest = &s[i + 1..];
+        let end_tag = rest
+            .strip_prefix('/')
+            .and_then(|o| o.get(..tag.len()))
+            .is_some_and(|o| o.eq_ignore_ascii_case(tag));
+        let comment = last_script.is_some_and(|j| i < j) && rest.starts_with("!--");
+        if end_tag {
+            w.write_str(&s[last..=i])?;
+            w.write_char('\\')?;
+            last = i + 1;
+        } else if comment {
+            w.write_str(&s[last..i])?;
+            w.write_str("\\u003c")?;
+            last = i + 1;
+        }
     }
+    w.write_str(&s[last..])
 }
 
 pub fn random_id(prefix: &str) -> String {
@@ -43,6 +186,27 @@ pub fn random_id(prefix: &str) -> String {
 mod test {
     use super::*;
 
+    #[test]
+    fn raw_text_keeps_json_valid() {
+        let json = r#"{"a": "<!-- <script> </script> <b>"}"#;
+        let mut res = String::new();
+        write_raw_text(json, "script", &mut res).unwrap();
+        assert_eq!(res, r#"{"a": "\u003c!-- <script> <\/script> <b>"}"#);
+        let value: serde_json::Value = serde_json::from_str(&res).unwrap();
+        assert_eq!(value["a"], "<!-- <script> </script> <b>");
+    }
+
+    #[test]
+    fn raw_text_keeps_comments_outside_strings() {
+        let js = "<!--\nif (a<!--b) {}\n//-->";
+        let mut res = String::new();
+        write_raw_text(js, "script", &mut res).unwrap();
+        assert_eq!(res, js);
+        let mut res = String::new();
+        write_raw_text("<!-- x --><script>", "style", &mut res).unwrap();
+        assert_eq!(res, "<!-- x --><script>");
+    }
+
     #[test]
     fn escape_html_works() {
         let res = escape_html("<a>\"hello\"</a>");
@@ -50,4 +214,17 @@ mod test {
         let res = escape_html("<a>'he \\& llo'</a>");
         insta::assert_snapshot!(res, @r"&lt;a&gt;&#x27;he \&amp; llo&#x27;&lt;/a&gt;");
     }
+
+    #[test]
+    fn unescape_html_works() {
+        let res = unescape_html("&lt;a&gt;&#x27;he &amp; llo&#39;&nbsp;&copy; &unknown; & &#0;");
+        assert_eq!(res, "<a>'he & llo'\u{a0}© &unknown; & &#0;");
+        let res =
+            unescape_html("&alpha; &hellip; &CounterClockwiseContourIntegral; &NotEqualTilde;");
+        assert_eq!(res, "α … ∳ \u{2242}\u{338}");
+        assert_eq!(
+            unescape_html(&escape_html("<a href='x'>&</a>")),
+            "<a href='x'>&</a>"
+        );
+    }
 }
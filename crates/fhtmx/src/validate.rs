@@ -0,0 +1,413 @@
+use crate::{
+    attribute::AttributeValue,
+    element::Element,
+    node::HtmlNode,
+    parse::CLOSES_P,
+    walk::{Walk, walk_nodes},
+};
+use std::{collections::HashMap, fmt, iter::Enumerate, slice, vec};
+
+/// Elements that can only be children of some elements. They are not checked at the top level
+/// (eg: a `li` rendered as an htmx partial), nor inside `template` or custom elements.
+const ALLOWED_PARENTS: &[(&str, &[&str])] = &[
+    ("caption", &["table"]),
+    ("col", &["colgroup"]),
+    ("colgroup", &["table"]),
+    ("dd", &["dl", "div"]),
+    ("dt", &["dl", "div"]),
+    ("figcaption", &["figure"]),
+    ("legend", &["fieldset"]),
+    ("li", &["ul", "ol", "menu"]),
+    ("optgroup", &["select"]),
+    ("option", &["select", "datalist", "optgroup"]),
+    ("source", &["audio", "video", "picture"]),
+    ("summary", &["details"]),
+    ("tbody", &["table"]),
+    ("td", &["tr"]),
+    ("tfoot", &["table"]),
+    ("th", &["tr"]),
+    ("thead", &["table"]),
+    ("tr", &["table", "thead", "tbody", "tfoot"]),
+    ("track", &["audio", "video"]),
+];
+
+/// Elements that can't be descendants of some elements
+const FORBIDDEN_ANCESTORS: &[(&str, &[&str])] = &[
+    ("a", &["a", "button"]),
+    ("button", &["a", "button"]),
+    ("form", &["form"]),
+    ("label", &["label"]),
+];
+
+/// Elements that hide the `p` elements around them from the parser (the button scope), so the
+/// elements of `CLOSES_P` don't close them
+const BUTTON_SCOPE: &[&str] = &[
+    "applet", "button", "caption", "html", "marquee", "math", "object", "svg", "table", "td",
+    "template", "th",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// An element outside of the elements it can be in (eg: a `li` in a `div`)
+    MisplacedElement {
+        tag: String,
+        allowed_parents: &'static [&'static str],
+    },
+    /// An element inside another one that can't contain it (eg: a `form` in a `form`)
+    NestedElement { tag: String, ancestor: String },
+    /// A block element inside a `p` (eg: a `div` or a `ul`), browsers close the `p` before it
+    BlockInParagraph { tag: String },
+    /// An `id` used by more than one element
+    DuplicateId { id: String, first_path: String },
+    /// Children in a void element (eg: `img`), they are not rendered
+    VoidWithChildren { tag: String },
+}
+
+/// Content-model violation found by `Validate::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub kind: ValidationErrorKind,
+    /// Path to the offending element, eg: `div > ul[1] > li[0]`, where each index is the position
+    /// among the element siblings (fragments are flattened)
+    pub path: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ValidationErrorKind::MisplacedElement {
+                tag,
+                allowed_parents,
+            } => {
+                let parents = allowed_parents
+                    .iter()
+                    .map(|o| format!("`{o}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "`{tag}` outside of {parents}")?
+            }
+            ValidationErrorKind::NestedElement { tag, ancestor } => {
+                write!(f, "`{tag}` inside `{ancestor}`")?
+            }
+            ValidationErrorKind::BlockInParagraph { tag } => {
+                write!(f, "block element `{tag}` inside `p`")?
+            }
+            ValidationErrorKind::DuplicateId { id, first_path } => {
+                write!(f, "duplicate id `{id}` (first used at `{first_path}`)")?
+            }
+            ValidationErrorKind::VoidWithChildren { tag } => {
+                write!(f, "children in void element `{tag}`")?
+            }
+        }
+        write!(f, " at `{}`", self.path)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks html against the content-model rules that browsers silently repair, so mistakes show
+/// up in tests instead of as a different DOM:
+///
+/// - Elements outside of their parents: `li` outside `ul`, `ol` or `menu`, `tr` outside a
+///   table, `option` outside `select`, etc.
+/// - Nested interactive elements and forms: `a` inside `a`, `form` inside `form`, etc.
+/// - Block elements (`div`, `ul`, `p`, etc) inside a `p`, even through inline elements.
+/// - Duplicated `id`s.
+/// - Children in void elements, which are not rendered.
+///
+/// Raw and pre-rendered nodes are not checked.
+///
+/// # Example
+///
+/// ```
+/// # use fhtmx::prelude::*;
+/// let errors = div().add(li().add("one")).add(p().add(div())).validate().unwrap_err();
+/// assert_eq!(errors[0].to_string(), "`li` outside of `ul`, `ol`, `menu` at `div > li[0]`");
+/// assert_eq!(errors[1].to_string(), "block element `div` inside `p` at `div > p[1] > div[0]`");
+/// ```
+pub trait Validate {
+    /// Checks the content-model rules, returning all the violations found
+    fn validate(&self) -> Result<(), Vec<ValidationError>>;
+}
+
+impl<T: Element> Validate for T {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut validator = Validator::default();
+        validator.visit(self, None);
+        validator.visit_descendants();
+        validator.finish()
+    }
+}
+
+impl Validate for HtmlNode {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut validator = Validator::default();
+        let roots = element_nodes(slice::from_ref(self));
+        for (i, node) in roots.iter().enumerate() {
+            validator.visit_node(node, (roots.len() > 1).then_some(i));
+            validator.visit_descendants();
+        }
+        validator.finish()
+    }
+}
+
+/// Validates before rendering with the `debug_validate` feature in debug builds
+#[cfg(all(debug_assertions, feature = "debug_validate"))]
+#[track_caller]
+pub(crate) fn debug_validate<T: Validate + ?Sized>(x: &T) {
+    if let Err(errors) = x.validate() {
+        let errors = errors
+            .iter()
+            .map(|o| format!("- {o}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        panic!("invalid html:\n{errors}");
+    }
+}
+
+/// Elements in `nodes`, flattening fragments
+pub(crate) fn element_nodes(nodes: &[HtmlNode]) -> Vec<&HtmlNode> {
+    let mut res = Vec::new();
+    walk_nodes(nodes, |o| match o {
+        HtmlNode::Fragment(_) => Walk::Continue,
+        HtmlNode::Element(_) | HtmlNode::SvgElement(_) => {
+            res.push(o);
+            Walk::SkipChildren
+        }
+        _ => Walk::SkipChildren,
+    });
+    res
+}
+
+/// An element being visited
+struct Frame<'a> {
+    tag: &'a str,
+    /// Path segment, eg: `li[2]`
+    segment: String,
+    /// Whether it's inside a `p` (or is one) that the parser would close
+    in_p: bool,
+    children: Enumerate<vec::IntoIter<&'a HtmlNode>>,
+}
+
+#[derive(Default)]
+struct Validator<'a> {
+    frames: Vec<Frame<'a>>,
+    /// Path of the first element using each id
+    ids: HashMap<&'a str, String>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'a> Validator<'a> {
+    /// Visits the children of the pending elements, until all of them are done
+    fn visit_descendants(&mut self) {
+        while let Some(frame) = self.frames.last_mut() {
+            match frame.children.next() {
+                Some((i, node)) => self.visit_node(node, Some(i)),
+                None => {
+                    self.frames.pop();
+                }
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), Vec<ValidationError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    fn visit_node(&mut self, node: &'a HtmlNode, index: Option<usize>) {
+        match node {
+            HtmlNode::Element(x) => self.visit(x, index),
+            HtmlNode::SvgElement(x) => self.visit(x, index),
+            _ => {}
+        }
+    }
+
+    /// Checks an element and schedules its children
+    fn visit<E: Element>(&mut self, el: &'a E, index: Option<usize>) {
+        let tag = el.tag();
+        let segment = match index {
+            Some(i) => format!("{tag}[{i}]"),
+            None => tag.to_string(),
+        };
+        let parent = self.frames.last();
+
+        if let Some(parent) = parent
+            && parent.tag != "template"
+            && !parent.tag.contains('-')
+            && let Some((_, allowed_parents)) = ALLOWED_PARENTS.iter().find(|(k, _)| *k == tag)
+            && !allowed_parents.contains(&parent.tag)
+        {
+            let kind = ValidationErrorKind::MisplacedElement {
+                tag: tag.to_string(),
+                allowed_parents,
+            };
+            self.error(kind, &segment);
+        }
+
+        if let Some((_, forbidden)) = FORBIDDEN_ANCESTORS.iter().find(|(k, _)| *k == tag)
+            && let Some(ancestor) = self
+                .frames
+                .iter()
+                .rev()
+                .find(|o| forbidden.contains(&o.tag))
+        {
+            let kind = ValidationErrorKind::NestedElement {
+                tag: tag.to_string(),
+                ancestor: ancestor.tag.to_string(),
+            };
+            self.error(kind, &segment);
+        }
+
+        let parent_in_p = self.frames.last().is_some_and(|o| o.in_p);
+        if parent_in_p && CLOSES_P.contains(&tag) {
+            let kind = ValidationErrorKind::BlockInParagraph {
+                tag: tag.to_string(),
+            };
+            self.error(kind, &segment);
+        }
+
+        if let Some(AttributeValue::Value(id) | AttributeValue::Raw(id)) = el.attrs().get("id") {
+            if let Some(first_path) = self.ids.get(id.as_str()) {
+                let kind = ValidationErrorKind::DuplicateId {
+                    id: id.clone(),
+                    first_path: first_path.clone(),
+                };
+                self.error(kind, &segment);
+            } else {
+                let path = self.path(&segment);
+                self.ids.insert(id, path);
+            }
+        }
+
+        if el.is_void_tag() && !el.children().is_empty() {
+            let kind = ValidationErrorKind::VoidWithChildren {
+                tag: tag.to_string(),
+            };
+            self.error(kind, &segment);
+        }
+
+        let in_p = tag == "p" || (parent_in_p && !BUTTON_SCOPE.contains(&tag));
+        self.frames.push(Frame {
+            tag,
+            segment,
+            in_p,
+            children: element_nodes(el.children()).into_iter().enumerate(),
+        });
+    }
+
+    fn path(&self, segment: &str) -> String {
+        self.frames
+            .iter()
+            .map(|o| o.segment.as_str())
+            .chain([segment])
+            .collect::<Vec<_>>()
+            .join(" > ")
+    }
+
+    fn error(&mut self, kind: ValidationErrorKind, segment: &str) {
+        let path = self.path(segment);
+        self.errors.push(ValidationError { kind, path });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        html_element::*,
+        node::{IntoNode, fragment},
+        svg::*,
+    };
+
+    fn errors(x: &impl Validate) -> String {
+        match x.validate() {
+            Ok(()) => String::new(),
+            Err(errors) => errors
+                .iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    #[test]
+    fn valid_html_passes() {
+        let page = body()
+            .add(
+                nav().add(
+                    ul().add(li().add(a().href("/").add(span().add("Home"))))
+                        .add(fragment([li().add("About")])),
+                ),
+            )
+            .add(
+                table()
+                    .add(thead().add(tr().add(th().add("Name"))))
+                    .add(tr().add(td().add("Row"))),
+            )
+            .add(
+                form()
+                    .id("form")
+                    .add(label().add("Name").add(input().name("name")))
+                    .add(select().add(option().add("a")))
+                    .add(button().add(b().add("Send"))),
+            )
+            .add(p().add(a().href("/").add(em().add("link"))))
+            .add(label().add(div().add("Name")))
+            .add(pre().add(div().add("code")))
+            .add(p().add(button().add(div().add("Send"))))
+            .add(template().add(tr().add(td())))
+            .add(custom_element("x-list").add(li().add("slotted")))
+            .add(
+                svg()
+                    .id("icon")
+                    .add(svg_a().add(svg_text().add("svg link"))),
+            );
+        assert_eq!(errors(&page), "");
+        // Partials can start with elements that need a parent
+        assert_eq!(errors(&fragment([li(), li()])), "");
+        assert_eq!(errors(&tr().add(td())), "");
+    }
+
+    #[test]
+    fn reports_content_model_errors() {
+        let page = main_tag()
+            .add(div().id("main").add(li().add("one")))
+            .add(p().add(span().add(a().href("/").add(div().id("main")))))
+            .add(form().add(fieldset().add(form())))
+            .add(a().href("/").add(button().add("nested")))
+            .add(ul().add(fragment([li(), p().add(h2())])))
+            .add(img().src("/a.png").add("not rendered"))
+            .add(table().add(td()));
+        insta::assert_snapshot!(errors(&page), @r"
+        `li` outside of `ul`, `ol`, `menu` at `main > div[0] > li[0]`
+        block element `div` inside `p` at `main > p[1] > span[0] > a[0] > div[0]`
+        duplicate id `main` (first used at `main > div[0]`) at `main > p[1] > span[0] > a[0] > div[0]`
+        `form` inside `form` at `main > form[2] > fieldset[0] > form[0]`
+        `button` inside `a` at `main > a[3] > button[0]`
+        block element `h2` inside `p` at `main > ul[4] > p[1] > h2[0]`
+        children in void element `img` at `main > img[5]`
+        `td` outside of `tr` at `main > table[6] > td[0]`
+        ");
+    }
+
+    #[test]
+    fn validate_nodes() {
+        let node = fragment([p().add(ul()).into_node(), p().add(ul()).into_node()]);
+        insta::assert_snapshot!(errors(&node), @r"
+        block element `ul` inside `p` at `p[0] > ul[0]`
+        block element `ul` inside `p` at `p[1] > ul[0]`
+        ");
+    }
+
+    #[cfg(all(debug_assertions, feature = "debug_validate"))]
+    #[test]
+    #[should_panic(expected = "invalid html:\n- block element `div` inside `p` at `p > div[0]`")]
+    fn debug_validate_on_render() {
+        use crate::render::Render;
+        p().add(div()).render();
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{element::Element, node::HtmlNode};
+use std::slice;
+
+/// What to do after visiting a node in `HtmlNode::walk` and `HtmlNode::walk_mut`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    /// Keep going, visiting the children of the node
+    Continue,
+    /// Keep going, but don't visit the children of the node
+    SkipChildren,
+    /// Stop the walk
+    Stop,
+}
+
+fn children(node: &HtmlNode) -> Option<&[HtmlNode]> {
+    match node {
+        HtmlNode::Element(x) => Some(x.children()),
+        HtmlNode::SvgElement(x) => Some(x.children()),
+        HtmlNode::Fragment(x) => Some(x),
+        _ => None,
+    }
+}
+
+fn children_mut(node: &mut HtmlNode) -> Option<&mut Vec<HtmlNode>> {
+    match node {
+        HtmlNode::Element(x) => Some(x.children_mut()),
+        HtmlNode::SvgElement(x) => Some(x.children_mut()),
+        HtmlNode::Fragment(x) => Some(x),
+        _ => None,
+    }
+}
+
+/// Visits `nodes` and their descendants in document order
+pub(crate) fn walk_nodes<'a>(nodes: &'a [HtmlNode], mut f: impl FnMut(&'a HtmlNode) -> Walk) {
+    let mut stack = vec![nodes.iter()];
+    while let Some(iter) = stack.last_mut() {
+        let Some(node) = iter.next() else {
+            stack.pop();
+            continue;
+        };
+        match f(node) {
+            Walk::Continue => {}
+            Walk::SkipChildren => continue,
+            Walk::Stop => return,
+        }
+        if let Some(children) = children(node) {
+            stack.push(children.iter());
+        }
+    }
+}
+
+/// Visits `nodes` and their descendants in document order, the children of a node are taken
+/// after calling `f` so they can be replaced
+pub(crate) fn walk_nodes_mut(nodes: &mut [HtmlNode], mut f: impl FnMut(&mut HtmlNode) -> Walk) {
+    let mut stack = vec![nodes.iter_mut()];
+    while let Some(iter) = stack.last_mut() {
+        let Some(node) = iter.next() else {
+            stack.pop();
+            continue;
+        };
+        match f(node) {
+            Walk::Continue => {}
+            Walk::SkipChildren => continue,
+            Walk::Stop => return,
+        }
+        if let Some(children) = children_mut(node) {
+            stack.push(children.iter_mut());
+        }
+    }
+}
+
+impl HtmlNode {
+    /// Visits the node and all its descendants in document order (elements, svg elements, text,
+    /// raw nodes and fragments). The callback decides if the walk continues with `Walk`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let page = div().id("a").add(p().id("b")).into_node();
+    /// let mut ids = Vec::new();
+    /// page.walk(|node| {
+    ///     if let Some(AttributeValue::Value(id)) = node.as_element().and_then(|o| o.attrs().get("id")) {
+    ///         ids.push(id.as_str());
+    ///     }
+    ///     Walk::Continue
+    /// });
+    /// assert_eq!(ids, ["a", "b"]);
+    /// ```
+    pub fn walk<'a>(&'a self, f: impl FnMut(&'a HtmlNode) -> Walk) {
+        walk_nodes(slice::from_ref(self), f);
+    }
+
+    /// Visits the node and all its descendants in document order, allowing to modify them.
+    /// Nodes can be replaced with `*node = ..`, the walk goes on with the children of the new node
+    /// (unless `Walk::SkipChildren` is returned).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fhtmx::prelude::*;
+    /// let mut page = div().add(img().src("/a.png")).into_node();
+    /// page.walk_mut(|node| {
+    ///     if let HtmlNode::Element(el) = node
+    ///         && el.tag() == "img"
+    ///     {
+    ///         el.set_attr_mut("loading", "lazy");
+    ///     }
+    ///     Walk::Continue
+    /// });
+    /// ```
+    pub fn walk_mut(&mut self, f: impl FnMut(&mut HtmlNode) -> Walk) {
+        walk_nodes_mut(slice::from_mut(self), f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        attribute::AttributeValue,
+        html_element::*,
+        node::{IntoNode, fragment, raw_node},
+        render::Render,
+        svg::*,
+    };
+
+    fn page() -> HtmlNode {
+        div()
+            .id("root")
+            .add(
+                nav()
+                    .add(a().href("/home").add("Home"))
+                    .add(a().href("https://example.com").add("Out")),
+            )
+            .add(fragment([
+                img().src("/a.png").into_node(),
+                p().id("intro")
+                    .add("Hello ")
+                    .add(b().add("world"))
+                    .into_node(),
+            ]))
+            .add(svg().id("icon").add(svg_path().set_attr("d", "M0 0")))
+            .add_raw("<!-- end -->")
+            .into_node()
+    }
+
+    #[test]
+    fn walk_visits_every_node() {
+        let page = fragment([HtmlNode::Doctype, page()]);
+        let mut visited = Vec::new();
+        page.walk(|node| {
+            visited.push(match node {
+                HtmlNode::Doctype => "doctype".to_string(),
+                HtmlNode::Raw(x) => format!("raw {x}"),
+                HtmlNode::Text(x) => format!("text {x:?}"),
+                HtmlNode::Element(x) => x.tag().to_string(),
+                HtmlNode::SvgElement(x) => format!("svg {}", x.tag()),
+                HtmlNode::Fragment(x) => format!("fragment {}", x.len()),
+                HtmlNode::Prerendered(x) => format!("prerendered {}", x.as_str()),
+            });
+            Walk::Continue
+        });
+        insta::assert_snapshot!(visited.join("\n"), @r#"
+        fragment 2
+        doctype
+        div
+        nav
+        a
+        text "Home"
+        a
+        text "Out"
+        img
+        p
+        text "Hello "
+        b
+        text "world"
+        svg svg
+        svg path
+        raw <!-- end -->
+        "#);
+    }
+
+    #[test]
+    fn walk_collects_ids() {
+        let page = page();
+        let mut ids = Vec::new();
+        page.walk(|node| {
+            let attrs = match node {
+                HtmlNode::Element(x) => x.attrs(),
+                HtmlNode::SvgElement(x) => x.attrs(),
+                _ => return Walk::Continue,
+            };
+            if let Some(AttributeValue::Value(id)) = attrs.get("id") {
+                ids.push(id.as_str());
+            }
+            Walk::Continue
+        });
+        assert_eq!(ids, ["root", "intro", "icon"]);
+    }
+
+    #[test]
+    fn walk_skips_and_stops() {
+        let page = page();
+        let mut tags = Vec::new();
+        page.walk(|node| match node.as_element() {
+            Some(x) if x.tag() == "nav" => Walk::SkipChildren,
+            Some(x) if x.tag() == "b" => Walk::Stop,
+            Some(x) => {
+                tags.push(x.tag());
+                Walk::Continue
+            }
+            None => Walk::Continue,
+        });
+        assert_eq!(tags, ["div", "img", "p"]);
+    }
+
+    #[test]
+    fn walk_mut_updates_attrs() {
+        let mut page = page();
+        page.walk_mut(|node| {
+            let HtmlNode::Element(el) = node else {
+                return Walk::Continue;
+            };
+            match el.tag() {
+                "img" => el.set_attr_mut("loading", "lazy"),
+                "a" => {
+                    if let Some(AttributeValue::Value(href)) = el.attrs_mut().get_mut("href")
+                        && href.starts_with('/')
+                    {
+                        href.insert_str(0, "/app");
+                    }
+                }
+                _ => {}
+            }
+            Walk::Continue
+        });
+        insta::assert_snapshot!(page.render(), @r#"
+        <div id="root">
+          <nav><a href="/app/home">Home</a><a href="https://example.com">Out</a></nav>
+          <img src="/a.png" loading="lazy" />
+          <p id="intro">Hello <b>world</b></p>
+          <svg xmlns="http://www.w3.org/2000/svg" id="icon">
+            <path d="M0 0"></path>
+          </svg>
+          <!-- end -->
+        </div>
+        "#);
+    }
+
+    #[test]
+    fn walk_mut_replaces_nodes() {
+        let mut page = page();
+        let mut texts = 0;
+        page.walk_mut(|node| match node {
+            HtmlNode::Raw(_) => {
+                *node = raw_node("<!-- removed -->");
+                Walk::Continue
+            }
+            HtmlNode::Element(x) if x.tag() == "b" => {
+                *node = i().add("everyone").into_node();
+                Walk::Continue
+            }
+            HtmlNode::Text(x) => {
+                texts += 1;
+                *x = x.to_uppercase();
+                Walk::Continue
+            }
+            HtmlNode::SvgElement(_) => Walk::SkipChildren,
+            _ => Walk::Continue,
+        });
+        // The replacement's children are visited too
+        assert_eq!(texts, 4);
+        insta::assert_snapshot!(page.render(), @r#"
+        <div id="root">
+          <nav><a href="/home">HOME</a><a href="https://example.com">OUT</a></nav>
+          <img src="/a.png" />
+          <p id="intro">HELLO <i>EVERYONE</i></p>
+          <svg xmlns="http://www.w3.org/2000/svg" id="icon">
+            <path d="M0 0"></path>
+          </svg>
+          <!-- removed -->
+        </div>
+        "#);
+    }
+}
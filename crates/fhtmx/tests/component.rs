@@ -0,0 +1,102 @@
+use fhtmx::prelude::*;
+use std::borrow::Cow;
+
+/// A card with a title
+#[component]
+fn card(
+    #[prop(into)] title: String,
+    subtitle: Option<String>,
+    #[prop(default = DaisyColor::Primary)] color: DaisyColor,
+    #[prop(default, into)] class: String,
+    #[prop(default)] compact: bool,
+    children: Vec<HtmlNode>,
+) -> HtmlElement {
+    let mut body = dc_card_body()
+        .add(h2().class("card-title").add(title))
+        .add_opt(subtitle.map(|o| p().add(o)));
+    body.add_children_mut(children);
+    dc_card()
+        .add_class(color.bg())
+        .add_class(class)
+        .add_opt_class(compact.then_some("card-sm"))
+        .add(body)
+}
+
+#[component]
+fn badges(items: Vec<&'static str>) -> HtmlNode {
+    HtmlNode::Fragment(
+        items
+            .into_iter()
+            .map(|o| dc_badge().add(o).into_node())
+            .collect(),
+    )
+}
+
+#[component]
+fn labelled<'a>(label: &'a str, hint: Option<&str>, value: Cow<'_, str>) -> HtmlElement {
+    let input = input().aria_label(label).value(value.into_owned());
+    div()
+        .add(label)
+        .add(input)
+        .add_opt(hint.map(|o| small().add(o)))
+}
+
+#[test]
+fn component_works() {
+    let res = div()
+        .add(card("Minimal"))
+        .add(
+            card("Full")
+                .subtitle("Some details".to_string())
+                .color(DaisyColor::Secondary)
+                .class("w-96")
+                .compact(true)
+                .add(p().add("Content"))
+                .add(badges(vec!["a", "b"])),
+        )
+        .render();
+    insta::assert_snapshot!(res, @r#"
+    <div>
+      <div class="card bg-primary">
+        <div class="card-body">
+          <h2 class="card-title">Minimal</h2>
+        </div>
+      </div>
+      <div class="card bg-secondary w-96 card-sm">
+        <div class="card-body">
+          <h2 class="card-title">Full</h2>
+          <p>Some details</p>
+          <p>Content</p>
+          <span class="badge">a</span>
+          <span class="badge">b</span>
+        </div>
+      </div>
+    </div>
+    "#);
+}
+
+#[test]
+fn component_into_element() {
+    fn wrap(x: impl IntoHtmlElement) -> HtmlElement {
+        x.into_element().add_class("wrapped")
+    }
+    let res = wrap(card("Title")).render();
+    insta::assert_snapshot!(res, @r#"
+    <div class="card bg-primary wrapped">
+      <div class="card-body">
+        <h2 class="card-title">Title</h2>
+      </div>
+    </div>
+    "#);
+}
+
+#[test]
+fn component_borrows_props() {
+    let label = String::from("Name");
+    let hint = String::from("As shown in your profile");
+    let res = labelled(&label, Cow::Borrowed("Karls"))
+        .hint(&hint)
+        .build()
+        .render();
+    insta::assert_snapshot!(res, @r#"<div>Name<input aria-label="Name" value="Karls" /><small>As shown in your profile</small></div>"#);
+}
@@ -0,0 +1,122 @@
+use fhtmx::prelude::*;
+
+fn page(user: Option<&str>, count: usize) -> HtmlElement {
+    let items = ["one", "two"];
+    html! {
+        main class="container" id=(format!("page-{count}")) {
+            h1 class="title" { "Hello " (user.unwrap_or("guest")) }
+            ul hx-get="/items" hx-trigger="load" {
+                @for (i, item) in items.iter().enumerate() {
+                    li data-index=(i) { (*item) }
+                }
+            }
+            @if let Some(name) = user {
+                a href="/logout" title=(name) { "Logout" }
+            } @else if count > 5 {
+                p { "Many" }
+            } @else {
+                dc_btn class="btn-primary" { "Login" }
+            }
+            @match count {
+                0 => { span { "none" } }
+                n if n < 5 => { span { "few" } }
+                _ => { span { "many" } }
+            }
+            input type="checkbox" checked[count > 1] disabled[count > 5] required;
+            a href="javascript:alert(1)" { "Bad" }
+            svg viewBox="0 0 24 24" stroke-width="2" {
+                linearGradient id="g";
+                path d="M0 0L24 24";
+            }
+        }
+    }
+}
+
+#[test]
+fn html_macro_works() {
+    let res = page(Some("Karls"), 3).render();
+    insta::assert_snapshot!(res, @r#"
+    <main class="container" id="page-3">
+      <h1 class="title">Hello Karls</h1>
+      <ul hx-get="/items" hx-trigger="load">
+        <li data-index="0">one</li>
+        <li data-index="1">two</li>
+      </ul>
+      <a href="/logout" title="Karls">Logout</a>
+      <span>few</span>
+      <input type="checkbox" checked required />
+      <a href="about:invalid">Bad</a>
+      <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" stroke-width="2">
+        <linearGradient id="g"></linearGradient>
+        <path d="M0 0L24 24"></path>
+      </svg>
+    </main>
+    "#);
+}
+
+#[test]
+fn html_macro_fragments() {
+    let res = html! {
+        "text"
+        p { "paragraph" }
+    };
+    insta::assert_snapshot!(res.render(), @r"
+    text
+    <p>paragraph</p>
+    ");
+
+    let show = false;
+    let res = html! {
+        @if show { p { "shown" } }
+        span { "always" }
+    };
+    insta::assert_snapshot!(res.render(), @"<span>always</span>");
+}
+
+fn card(name: &str, tags: &[&str]) -> HtmlElement {
+    rsx! {
+        <div class="card" hx-post={format!("/users/{name}")}>
+            <p>"Hello " <b>{name}</b></p>
+            <br>
+            @for tag in tags {
+                <span class="badge">{*tag}</span>
+            }
+            <input type="text" value={name} disabled[name.is_empty()]/>
+        </div>
+    }
+}
+
+#[test]
+fn rsx_macro_works() {
+    let res = card("Karls", &["a", "b"]).render();
+    insta::assert_snapshot!(res, @r#"
+    <div class="card" hx-post="/users/Karls">
+      <p>Hello <b>Karls</b></p>
+      <br />
+      <span class="badge">a</span>
+      <span class="badge">b</span>
+      <input type="text" value="Karls" />
+    </div>
+    "#);
+}
+
+#[test]
+fn html_macro_custom_elements() {
+    let res = html! {
+        my-card class="card" {
+            template shadowrootmode="open" {
+                slot name="title";
+            }
+            x-chart-2 data-src="/stats";
+        }
+    }
+    .render();
+    insta::assert_snapshot!(res, @r#"
+    <my-card class="card">
+      <template shadowrootmode="open">
+        <slot name="title"></slot>
+      </template>
+      <x-chart-2 data-src="/stats"></x-chart-2>
+    </my-card>
+    "#);
+}